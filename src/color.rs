@@ -1,5 +1,6 @@
 #[derive(Clone, Copy, Debug)]
 pub enum Color {
+    Failure,
     Success,
     Pending,
 }
@@ -7,6 +8,7 @@ pub enum Color {
 impl From<Color> for u32 {
     fn from(color: Color) -> Self {
         match color {
+            Color::Failure => 0x00ED_4245,
             Color::Success => 0x0057_F287,
             Color::Pending => 0x00FE_E75C,
         }
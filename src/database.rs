@@ -0,0 +1,722 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension as _, Row};
+use twilight_model::{
+    id::{marker::UserMarker, Id},
+    util::Timestamp,
+};
+
+use crate::{
+    model::{
+        audit::{AuditAction, AuditLogEntry},
+        member::MemberJoin,
+        outbox::{OutboxEntry, OutboxOperation},
+        reminder::ReminderKind,
+        verification::{FormAnswer, VerificationSubmission, VerificationSubmissionRecord},
+    },
+    store::SubmissionStore,
+};
+
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE verification_submissions (
+        id INTEGER PRIMARY KEY,
+        user_id INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        status_reason TEXT,
+        message_id INTEGER,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX verification_submissions_user_id ON verification_submissions (user_id);
+    CREATE TABLE verification_submission_answers (
+        submission_id INTEGER NOT NULL REFERENCES verification_submissions (id),
+        position INTEGER NOT NULL,
+        custom_id TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (submission_id, custom_id)
+    );",
+    "CREATE TABLE outbox (
+        id INTEGER PRIMARY KEY,
+        store TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        submission_id INTEGER NOT NULL,
+        attempts INTEGER NOT NULL,
+        last_error TEXT NOT NULL,
+        next_attempt_at INTEGER NOT NULL,
+        created_at INTEGER NOT NULL
+    );",
+    "CREATE TABLE submission_attempts (
+        user_id INTEGER NOT NULL,
+        attempted_at INTEGER NOT NULL
+    );
+    CREATE INDEX submission_attempts_attempted_at ON submission_attempts (attempted_at);",
+    "ALTER TABLE verification_submissions ADD COLUMN reviewer_id INTEGER;
+    ALTER TABLE verification_submissions ADD COLUMN reviewed_at INTEGER;
+    CREATE TABLE audit_log (
+        id INTEGER PRIMARY KEY,
+        submission_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        reviewer_id INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        reason TEXT,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX audit_log_submission_id ON audit_log (submission_id);",
+    "CREATE TABLE submission_reminders (
+        submission_id INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        sent_at INTEGER NOT NULL,
+        PRIMARY KEY (submission_id, kind)
+    );",
+    "CREATE TABLE member_joins (
+        user_id INTEGER PRIMARY KEY,
+        joined_at INTEGER NOT NULL,
+        reminded_at INTEGER,
+        actioned_at INTEGER
+    );",
+    "ALTER TABLE verification_submissions ADD COLUMN revoker_id INTEGER;
+    ALTER TABLE verification_submissions ADD COLUMN revoked_at INTEGER;",
+];
+
+const VERIFICATION_SUBMISSION_COLUMNS: &str = "id, user_id, status, status_reason, message_id, \
+                                               created_at, updated_at, reviewer_id, reviewed_at, \
+                                               revoker_id, revoked_at";
+
+pub struct Database(Mutex<Connection>);
+
+impl Database {
+    /// Records who reviewed or revoked the submission, the record should
+    /// already be reviewed or revoked.
+    pub fn add_audit_log_entry(
+        &self,
+        record: &VerificationSubmissionRecord,
+        action: AuditAction,
+    ) -> Result<()> {
+        let (reviewer_id, created_at) = match action {
+            AuditAction::Approve | AuditAction::Reject => (record.reviewer_id, record.reviewed_at),
+            AuditAction::Revoke => (record.revoker_id, record.revoked_at),
+        };
+
+        self.connection()?.execute(
+            "INSERT INTO audit_log
+                 (submission_id, user_id, reviewer_id, action, reason, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                record.id,
+                record.submission.user_id.get(),
+                reviewer_id
+                    .ok_or_else(|| anyhow!("audited submission has no reviewer"))?
+                    .get(),
+                action.as_str(),
+                record.status_reason,
+                created_at.unwrap_or(record.updated_at).as_secs(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Starts tracking the member, resetting the tracking if they joined
+    /// before.
+    pub fn add_member_join(&self, user_id: Id<UserMarker>, joined_at: i64) -> Result<()> {
+        self.connection()?.execute(
+            "INSERT OR REPLACE INTO member_joins (user_id, joined_at) VALUES (?1, ?2)",
+            params![user_id.get(), joined_at],
+        )?;
+
+        Ok(())
+    }
+
+    /// Adds the members who aren't tracked yet, keeping the ones that are.
+    pub fn add_missing_member_joins(&self, member_joins: &[(Id<UserMarker>, i64)]) -> Result<()> {
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+
+            for &(user_id, joined_at) in member_joins {
+                transaction.execute(
+                    "INSERT OR IGNORE INTO member_joins (user_id, joined_at) VALUES (?1, ?2)",
+                    params![user_id.get(), joined_at],
+                )?;
+            }
+
+            transaction.commit()?;
+
+            Ok(())
+        })
+    }
+
+    pub fn add_reminded_submissions(
+        &self,
+        kind: ReminderKind,
+        submission_ids: &[u64],
+        sent_at: i64,
+    ) -> Result<()> {
+        self.with_connection(|connection| {
+            for submission_id in submission_ids {
+                connection.execute(
+                    "INSERT OR IGNORE INTO submission_reminders (submission_id, kind, sent_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![submission_id, kind.as_str(), sent_at],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Saves the submission attempt, deleting the ones that are made before
+    /// `forget_before`.
+    pub fn add_submission_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        attempted_at: i64,
+        forget_before: i64,
+    ) -> Result<()> {
+        self.with_connection(|connection| {
+            connection.execute(
+                "DELETE FROM submission_attempts WHERE attempted_at <= ?1",
+                [forget_before],
+            )?;
+            connection.execute(
+                "INSERT INTO submission_attempts (user_id, attempted_at) VALUES (?1, ?2)",
+                params![user_id.get(), attempted_at],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    fn answers(
+        connection: &Connection,
+        submission_id: Option<u64>,
+    ) -> Result<HashMap<u64, Vec<FormAnswer>>> {
+        let mut statement = connection.prepare(
+            "SELECT submission_id, custom_id, value FROM verification_submission_answers
+             WHERE ?1 IS NULL OR submission_id = ?1
+             ORDER BY submission_id, position",
+        )?;
+
+        let mut answers: HashMap<u64, Vec<FormAnswer>> = HashMap::new();
+        for row in statement.query_map([submission_id], |row| {
+            Ok((
+                row.get("submission_id")?,
+                FormAnswer {
+                    custom_id: row.get("custom_id")?,
+                    value: row.get("value")?,
+                },
+            ))
+        })? {
+            let (answer_submission_id, answer) = row?;
+            answers
+                .entry(answer_submission_id)
+                .or_default()
+                .push(answer);
+        }
+
+        Ok(answers)
+    }
+
+    /// Returns the actions taken on the user's submissions, newest first.
+    pub fn audit_log_entries(&self, user_id: Id<UserMarker>) -> Result<Vec<AuditLogEntry>> {
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT submission_id, reviewer_id, action, reason, created_at FROM audit_log \
+                 WHERE user_id = ?1 ORDER BY created_at DESC, id DESC",
+            )?;
+
+            let mut entries = vec![];
+            for entry in
+                statement.query_map([user_id.get()], |row| Ok(Self::audit_log_entry(row)))?
+            {
+                entries.push(entry??);
+            }
+
+            Ok(entries)
+        })
+    }
+
+    fn audit_log_entry(row: &Row<'_>) -> Result<AuditLogEntry> {
+        Ok(AuditLogEntry {
+            action: row.get::<_, String>("action")?.parse()?,
+            created_at: Timestamp::from_secs(row.get("created_at")?)?,
+            reason: row.get("reason")?,
+            reviewer_id: Id::new_checked(row.get("reviewer_id")?)
+                .ok_or_else(|| anyhow!("reviewer id is zero"))?,
+            submission_id: row.get("submission_id")?,
+        })
+    }
+
+    fn connection(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.0
+            .lock()
+            .map_err(|err| anyhow!("database connection mutex is poisoned: {err}"))
+    }
+
+    pub fn delete_member_join(&self, user_id: Id<UserMarker>) -> Result<()> {
+        self.connection()?.execute(
+            "DELETE FROM member_joins WHERE user_id = ?1",
+            [user_id.get()],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn delete_outbox_entry(&self, id: u64) -> Result<()> {
+        self.connection()?
+            .execute("DELETE FROM outbox WHERE id = ?1", [id])?;
+
+        Ok(())
+    }
+
+    /// Adds an entry to the outbox unless the same operation is already queued.
+    pub fn enqueue_outbox_entry(
+        &self,
+        store: &str,
+        operation: OutboxOperation,
+        submission_id: u64,
+        error: &str,
+        next_attempt_at: Timestamp,
+    ) -> Result<()> {
+        self.connection()?.execute(
+            "INSERT INTO outbox
+                 (store, operation, submission_id, attempts, last_error, next_attempt_at,
+                  created_at)
+             SELECT ?1, ?2, ?3, 1, ?4, ?5, unixepoch()
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM outbox WHERE store = ?1 AND operation = ?2 AND submission_id = ?3
+             )",
+            params![
+                store,
+                operation.as_str(),
+                submission_id,
+                error,
+                next_attempt_at.as_secs()
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn has_outbox_entry(&self, store: &str, submission_id: u64) -> Result<bool> {
+        Ok(self.connection()?.query_row(
+            "SELECT EXISTS (SELECT 1 FROM outbox WHERE store = ?1 AND submission_id = ?2)",
+            params![store, submission_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn member_joins(&self) -> Result<Vec<MemberJoin>> {
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT user_id, joined_at, reminded_at, actioned_at FROM member_joins
+                 ORDER BY joined_at",
+            )?;
+
+            let mut member_joins = vec![];
+            for member_join in statement.query_map([], |row| {
+                Ok((
+                    row.get::<_, u64>("user_id")?,
+                    row.get("joined_at")?,
+                    row.get("reminded_at")?,
+                    row.get("actioned_at")?,
+                ))
+            })? {
+                let (user_id, joined_at, reminded_at, actioned_at) = member_join?;
+                member_joins.push(MemberJoin {
+                    actioned_at,
+                    joined_at,
+                    reminded_at,
+                    user_id: Id::new_checked(user_id).ok_or_else(|| anyhow!("user id is zero"))?,
+                });
+            }
+
+            Ok(member_joins)
+        })
+    }
+
+    fn migrate(connection: &mut Connection) -> Result<()> {
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        let transaction = connection.transaction()?;
+        for migration in MIGRATIONS.iter().skip(version) {
+            transaction.execute_batch(migration)?;
+        }
+        transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    pub fn new(path: &str) -> Result<Self> {
+        let mut connection = Connection::open(path)?;
+        Self::migrate(&mut connection)?;
+
+        Ok(Self(Mutex::new(connection)))
+    }
+
+    pub fn outbox_entries(&self) -> Result<Vec<OutboxEntry>> {
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT id, store, operation, submission_id, attempts, last_error, \
+                 next_attempt_at,
+                        created_at
+                 FROM outbox ORDER BY id",
+            )?;
+
+            let mut entries = vec![];
+            for entry in statement.query_map([], |row| Ok(Self::outbox_entry(row)))? {
+                entries.push(entry??);
+            }
+
+            Ok(entries)
+        })
+    }
+
+    fn outbox_entry(row: &Row<'_>) -> Result<OutboxEntry> {
+        Ok(OutboxEntry {
+            attempts: row.get("attempts")?,
+            created_at: Timestamp::from_secs(row.get("created_at")?)?,
+            id: row.get("id")?,
+            last_error: row.get("last_error")?,
+            next_attempt_at: Timestamp::from_secs(row.get("next_attempt_at")?)?,
+            operation: row.get::<_, String>("operation")?.parse()?,
+            store: row.get("store")?,
+            submission_id: row.get("submission_id")?,
+        })
+    }
+
+    /// Returns the ids of the submissions that the reminder was sent for.
+    pub fn reminded_submissions(&self, kind: ReminderKind) -> Result<HashSet<u64>> {
+        self.with_connection(|connection| {
+            let mut statement = connection
+                .prepare("SELECT submission_id FROM submission_reminders WHERE kind = ?1")?;
+
+            let submission_ids = statement
+                .query_map([kind.as_str()], |row| row.get("submission_id"))?
+                .collect::<Result<_, _>>()?;
+
+            Ok(submission_ids)
+        })
+    }
+
+    pub fn set_member_actioned(&self, user_id: Id<UserMarker>, actioned_at: i64) -> Result<()> {
+        self.connection()?.execute(
+            "UPDATE member_joins SET actioned_at = ?2 WHERE user_id = ?1",
+            params![user_id.get(), actioned_at],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_member_reminded(&self, user_id: Id<UserMarker>, reminded_at: i64) -> Result<()> {
+        self.connection()?.execute(
+            "UPDATE member_joins SET reminded_at = ?2 WHERE user_id = ?1",
+            params![user_id.get(), reminded_at],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_outbox_entry_failed(
+        &self,
+        id: u64,
+        error: &str,
+        next_attempt_at: Timestamp,
+    ) -> Result<()> {
+        self.connection()?.execute(
+            "UPDATE outbox
+             SET attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3
+             WHERE id = ?1",
+            params![id, error, next_attempt_at.as_secs()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns the users and times of the submissions made after the given
+    /// time, oldest first.
+    pub fn submission_attempts(&self, since: i64) -> Result<Vec<(Id<UserMarker>, i64)>> {
+        self.with_connection(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT user_id, attempted_at FROM submission_attempts
+                 WHERE attempted_at > ?1 ORDER BY attempted_at",
+            )?;
+
+            let mut attempts = vec![];
+            for attempt in statement.query_map([since], |row| {
+                Ok((row.get::<_, u64>("user_id")?, row.get("attempted_at")?))
+            })? {
+                let (user_id, attempted_at) = attempt?;
+                attempts.push((
+                    Id::new_checked(user_id).ok_or_else(|| anyhow!("user id is zero"))?,
+                    attempted_at,
+                ));
+            }
+
+            Ok(attempts)
+        })
+    }
+
+    fn verification_submission_record(row: &Row<'_>) -> Result<VerificationSubmissionRecord> {
+        Ok(VerificationSubmissionRecord {
+            created_at: Timestamp::from_secs(row.get("created_at")?)?,
+            id: row.get("id")?,
+            message_id: row
+                .get::<_, Option<u64>>("message_id")?
+                .map(|message_id| {
+                    Id::new_checked(message_id).ok_or_else(|| anyhow!("message id is zero"))
+                })
+                .transpose()?,
+            reviewed_at: row
+                .get::<_, Option<i64>>("reviewed_at")?
+                .map(Timestamp::from_secs)
+                .transpose()?,
+            reviewer_id: row
+                .get::<_, Option<u64>>("reviewer_id")?
+                .map(|reviewer_id| {
+                    Id::new_checked(reviewer_id).ok_or_else(|| anyhow!("reviewer id is zero"))
+                })
+                .transpose()?,
+            revoked_at: row
+                .get::<_, Option<i64>>("revoked_at")?
+                .map(Timestamp::from_secs)
+                .transpose()?,
+            revoker_id: row
+                .get::<_, Option<u64>>("revoker_id")?
+                .map(|revoker_id| {
+                    Id::new_checked(revoker_id).ok_or_else(|| anyhow!("revoker id is zero"))
+                })
+                .transpose()?,
+            status: row.get::<_, String>("status")?.parse()?,
+            status_reason: row.get("status_reason")?,
+            submission: VerificationSubmission {
+                answers: vec![],
+                user_id: Id::new_checked(row.get("user_id")?)
+                    .ok_or_else(|| anyhow!("user id is zero"))?,
+            },
+            updated_at: Timestamp::from_secs(row.get("updated_at")?)?,
+        })
+    }
+
+    /// Runs the function with the connection locked, for the functions that
+    /// use it more than once.
+    fn with_connection<T, F: FnOnce(&mut Connection) -> Result<T>>(&self, run: F) -> Result<T> {
+        run(&mut *self.connection()?)
+    }
+}
+
+impl SubmissionStore for Database {
+    const NAME: &'static str = "sqlite";
+
+    async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+
+            transaction.execute(
+                &format!(
+                    "INSERT INTO verification_submissions ({VERIFICATION_SUBMISSION_COLUMNS}) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+                ),
+                params![
+                    record.id,
+                    record.submission.user_id.get(),
+                    record.status.as_str(),
+                    record.status_reason,
+                    record.message_id.map(Id::get),
+                    record.created_at.as_secs(),
+                    record.updated_at.as_secs(),
+                    record.reviewer_id.map(Id::get),
+                    record.reviewed_at.map(Timestamp::as_secs),
+                    record.revoker_id.map(Id::get),
+                    record.revoked_at.map(Timestamp::as_secs),
+                ],
+            )?;
+
+            for (position, answer) in record.submission.answers.iter().enumerate() {
+                transaction.execute(
+                    "INSERT INTO verification_submission_answers
+                         (submission_id, position, custom_id, value)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![record.id, position, answer.custom_id, answer.value],
+                )?;
+            }
+
+            transaction.commit()?;
+
+            Ok(())
+        })
+    }
+
+    async fn submission(&self, id: u64) -> Result<Option<VerificationSubmissionRecord>> {
+        self.with_connection(|connection| {
+            let Some(mut record) = connection
+                .query_row(
+                    &format!(
+                        "SELECT {VERIFICATION_SUBMISSION_COLUMNS} FROM verification_submissions \
+                         WHERE id = ?1"
+                    ),
+                    [id],
+                    |row| Ok(Self::verification_submission_record(row)),
+                )
+                .optional()?
+                .transpose()?
+            else {
+                return Ok(None);
+            };
+
+            record.submission.answers = Self::answers(connection, Some(id))?
+                .remove(&id)
+                .unwrap_or_default();
+
+            Ok(Some(record))
+        })
+    }
+
+    async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        self.with_connection(|connection| {
+            let mut answers = Self::answers(connection, None)?;
+            let mut statement = connection.prepare(&format!(
+                "SELECT {VERIFICATION_SUBMISSION_COLUMNS} FROM verification_submissions ORDER BY \
+                 id"
+            ))?;
+
+            let mut records = vec![];
+            for row in
+                statement.query_map([], |row| Ok(Self::verification_submission_record(row)))?
+            {
+                let mut record = row??;
+                record.submission.answers = answers.remove(&record.id).unwrap_or_default();
+                records.push(record);
+            }
+
+            Ok(records)
+        })
+    }
+
+    async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        self.connection()?.execute(
+            "UPDATE verification_submissions
+             SET status = ?2, status_reason = ?3, message_id = ?4, updated_at = ?5,
+                 reviewer_id = ?6, reviewed_at = ?7, revoker_id = ?8, revoked_at = ?9
+             WHERE id = ?1",
+            params![
+                record.id,
+                record.status.as_str(),
+                record.status_reason,
+                record.message_id.map(Id::get),
+                record.updated_at.as_secs(),
+                record.reviewer_id.map(Id::get),
+                record.reviewed_at.map(Timestamp::as_secs),
+                record.revoker_id.map(Id::get),
+                record.revoked_at.map(Timestamp::as_secs),
+            ],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::Database;
+    use crate::{
+        model::{
+            audit::AuditAction,
+            verification::{
+                VerificationStatus, VerificationSubmission, VerificationSubmissionRecord,
+            },
+        },
+        store::SubmissionStore as _,
+    };
+
+    #[test]
+    fn keeps_tracked_member_joins() {
+        let db = Database::new(":memory:").unwrap();
+        db.add_member_join(Id::new(1), 100).unwrap();
+        db.set_member_reminded(Id::new(1), 200).unwrap();
+
+        db.add_missing_member_joins(&[(Id::new(1), 50), (Id::new(2), 60)])
+            .unwrap();
+
+        let member_joins = db
+            .member_joins()
+            .unwrap()
+            .into_iter()
+            .map(|member_join| {
+                (
+                    member_join.user_id.get(),
+                    member_join.joined_at,
+                    member_join.reminded_at,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(member_joins, [(2, 60, None), (1, 100, Some(200))]);
+    }
+
+    #[test]
+    fn reads_audit_log_of_user() {
+        let db = Database::new(":memory:").unwrap();
+        for (id, user_id, status, action) in [
+            (1, 1, VerificationStatus::Rejected, AuditAction::Reject),
+            (2, 2, VerificationStatus::Approved, AuditAction::Approve),
+            (3, 1, VerificationStatus::Approved, AuditAction::Approve),
+        ] {
+            let mut record = VerificationSubmissionRecord::new(
+                id,
+                VerificationSubmission {
+                    answers: vec![],
+                    user_id: Id::new(user_id),
+                },
+            )
+            .unwrap();
+            record
+                .review(status, Some(format!("sebep {id}")), Id::new(10))
+                .unwrap();
+            db.add_audit_log_entry(&record, action).unwrap();
+        }
+
+        let entries = db.audit_log_entries(Id::new(1)).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.submission_id, entry.action))
+                .collect::<Vec<_>>(),
+            [(3, AuditAction::Approve), (1, AuditAction::Reject)]
+        );
+        assert_eq!(entries[1].reason.as_deref(), Some("sebep 1"));
+        assert_eq!(entries[1].reviewer_id, Id::new(10));
+    }
+
+    #[tokio::test]
+    async fn migrates_only_new_versions() {
+        let db = Database::new(":memory:").unwrap();
+        let record = VerificationSubmissionRecord::new(
+            1,
+            VerificationSubmission {
+                answers: vec![],
+                user_id: Id::new(1),
+            },
+        )
+        .unwrap();
+        db.append(&record).await.unwrap();
+
+        Database::migrate(&mut db.connection().unwrap()).unwrap();
+
+        assert!(db.submission(1).await.unwrap().is_some());
+        // the stores set the times of the submissions
+        db.connection()
+            .unwrap()
+            .execute(
+                "INSERT INTO verification_submissions (id, user_id, status) VALUES (2, 1, \
+                 'pending')",
+                [],
+            )
+            .unwrap_err();
+    }
+}
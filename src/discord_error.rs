@@ -0,0 +1,20 @@
+use twilight_http::{
+    api_error::{ApiError, GeneralApiError},
+    error::ErrorType,
+};
+
+/// The error code Discord responds with when the member isn't in the guild.
+pub const UNKNOWN_MEMBER_ERROR_CODE: u64 = 10007;
+/// The error code Discord responds with when the role doesn't exist.
+pub const UNKNOWN_ROLE_ERROR_CODE: u64 = 10011;
+
+/// Returns whether Discord responded to the request with the given error code.
+pub const fn is_api_error(err: &twilight_http::Error, code: u64) -> bool {
+    matches!(
+        err.kind(),
+        ErrorType::Response {
+            error: ApiError::General(GeneralApiError { code: error_code, .. }),
+            ..
+        } if *error_code == code
+    )
+}
@@ -1,29 +1,73 @@
 mod approve_verification;
 pub mod create_verification_message;
+mod flush_outbox;
+mod pending_submissions_page;
+mod reject_verification;
+mod reject_verification_modal_submit;
+mod search_submissions;
+mod show_outbox;
+pub mod show_pending_submissions;
+mod show_submission_info;
 pub mod show_verification_modal;
+mod unverify_member;
+mod user_submission_info;
 pub mod verification_modal_submit;
+mod verify_member;
+
+use std::fmt::Display;
 
 use anyhow::{anyhow, bail, Result};
+use tracing::{error, info, warn};
 use twilight_http::client::InteractionClient;
 use twilight_model::{
     application::{
         command::Command,
-        interaction::{Interaction, InteractionData},
+        interaction::{
+            application_command::CommandData, Interaction, InteractionData, InteractionType,
+        },
+    },
+    channel::{
+        message::{embed::Embed, Component, MessageFlags},
+        Message,
     },
-    http::interaction::InteractionResponse,
+    guild::Permissions,
+    http::interaction::{InteractionResponse, InteractionResponseType},
     id::{marker::InteractionMarker, Id},
 };
+use twilight_util::builder::{embed::EmbedBuilder, InteractionResponseDataBuilder};
 
 use crate::{
+    color::Color,
     interaction::{
         approve_verification::ApproveVerification,
-        create_verification_message::CreateVerificationMessage,
-        show_verification_modal::ShowVerificationModal,
-        verification_modal_submit::VerificationModalSubmit,
+        create_verification_message::CreateVerificationMessage, flush_outbox::FlushOutbox,
+        pending_submissions_page::PendingSubmissionsPage, reject_verification::RejectVerification,
+        reject_verification_modal_submit::RejectVerificationModalSubmit,
+        search_submissions::SearchSubmissions, show_outbox::ShowOutbox,
+        show_pending_submissions::ShowPendingSubmissions, show_submission_info::ShowSubmissionInfo,
+        show_verification_modal::ShowVerificationModal, unverify_member::UnverifyMember,
+        user_submission_info::UserSubmissionInfo,
+        verification_modal_submit::VerificationModalSubmit, verify_member::VerifyMember,
+    },
+    model::verification::{
+        FormAnswer, VerificationStatus, VerificationSubmission, VerificationSubmissionRecord,
     },
     Context,
 };
 
+const CUSTOM_ID_SEPARATOR: char = ':';
+
+/// Who can use an interaction, given where the interaction is dispatched so
+/// that every interaction has to state it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    Everyone,
+    /// The members with a reviewer role or the Manage Server permission,
+    /// checked even for the commands that Discord only shows them since the
+    /// commands' permissions can be changed in the server settings.
+    Reviewers,
+}
+
 pub trait CreateCommand {
     fn command() -> Result<Command>;
 }
@@ -45,6 +89,68 @@ pub struct InteractionContext {
 }
 
 impl InteractionContext {
+    pub async fn create_response(self, response: &InteractionResponse) -> Result<()> {
+        self.core
+            .interaction_client()
+            .create_response(self.id, &self.token, response)
+            .await?;
+        Ok(())
+    }
+
+    /// Acknowledges the interaction so that it can be responded to later
+    /// with [`Self::followup`] or [`Self::update_response`], which has to be
+    /// done in 3 seconds otherwise.
+    pub async fn defer(&self, ephemeral: bool) -> Result<()> {
+        let mut data = InteractionResponseDataBuilder::new();
+        if ephemeral {
+            data = data.flags(MessageFlags::EPHEMERAL);
+        }
+
+        self.core
+            .interaction_client()
+            .create_response(
+                self.id,
+                &self.token,
+                &InteractionResponse {
+                    kind: InteractionResponseType::DeferredChannelMessageWithSource,
+                    data: Some(data.build()),
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Acknowledges the component interaction so that its message can be
+    /// updated later with [`Self::update_response`].
+    pub async fn defer_update(&self) -> Result<()> {
+        self.core
+            .interaction_client()
+            .create_response(
+                self.id,
+                &self.token,
+                &InteractionResponse {
+                    kind: InteractionResponseType::DeferredUpdateMessage,
+                    data: None,
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn followup(&self, embeds: &[Embed], ephemeral: bool) -> Result<()> {
+        let client = self.core.interaction_client();
+        let mut followup = client.create_followup(&self.token).embeds(embeds)?;
+        if ephemeral {
+            followup = followup.flags(MessageFlags::EPHEMERAL);
+        }
+
+        followup.await?;
+
+        Ok(())
+    }
+
     pub fn new(ctx: Context, interaction: &Interaction) -> Self {
         Self {
             core: ctx,
@@ -53,76 +159,399 @@ impl InteractionContext {
         }
     }
 
-    pub async fn create_response(self, response: &InteractionResponse) -> Result<()> {
+    /// Tells the reviewer that someone else has already reviewed the
+    /// submission, the interaction should be deferred.
+    pub async fn respond_already_reviewed(
+        &self,
+        record: &VerificationSubmissionRecord,
+    ) -> Result<()> {
+        let action = match record.status {
+            VerificationStatus::Approved => "doğrulanmış",
+            VerificationStatus::Rejected => "reddedilmiş",
+            VerificationStatus::Pending => "incelenmiş",
+            VerificationStatus::Revoked => "doğrulaması kaldırılmış",
+        };
+        let reviewed_by = if record.status == VerificationStatus::Revoked {
+            (record.revoker_id, record.revoked_at)
+        } else {
+            (record.reviewer_id, record.reviewed_at)
+        };
+        let description = match reviewed_by {
+            (Some(reviewer_id), Some(reviewed_at)) => format!(
+                "Bu form <t:{}:R> <@{reviewer_id}> tarafından zaten {action}.",
+                reviewed_at.as_secs()
+            ),
+            _ => format!("Bu form zaten {action}."),
+        };
+
+        let embed = EmbedBuilder::new()
+            .title("ℹ️ Form zaten incelenmiş")
+            .description(description)
+            .color(Color::Pending.into())
+            .build();
+
+        self.followup(&[embed], true).await
+    }
+
+    /// Tells the user that handling the interaction failed, following up if
+    /// the interaction was already responded to.
+    async fn respond_error(&self, incident_id: &str) -> Result<()> {
+        let embed = EmbedBuilder::new()
+            .title("⚠️ Bir hata oluştu")
+            .description(format!(
+                "İsteğiniz işlenirken beklenmedik bir hata oluştu, lütfen daha sonra tekrar \
+                 deneyin. Sorun devam ederse yetkililere şu kodu iletin: `{incident_id}`"
+            ))
+            .color(Color::Failure.into())
+            .build();
+
+        let response = InteractionResponse {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(
+                InteractionResponseDataBuilder::new()
+                    .embeds([embed.clone()])
+                    .flags(MessageFlags::EPHEMERAL)
+                    .build(),
+            ),
+        };
+
+        if self
+            .core
+            .interaction_client()
+            .create_response(self.id, &self.token, &response)
+            .await
+            .is_err()
+        {
+            self.followup(&[embed], true).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Suggests nothing for the autocomplete interaction, since they can only
+    /// be responded to with choices.
+    async fn respond_no_choices(self) -> Result<()> {
+        self.create_response(&InteractionResponse {
+            kind: InteractionResponseType::ApplicationCommandAutocompleteResult,
+            data: Some(InteractionResponseDataBuilder::new().choices([]).build()),
+        })
+        .await
+    }
+
+    async fn respond_unauthorized(self, kind: InteractionType) -> Result<()> {
+        if kind == InteractionType::ApplicationCommandAutocomplete {
+            return self.respond_no_choices().await;
+        }
+
+        let embed = EmbedBuilder::new()
+            .title("⛔ Bunu yapma yetkiniz yok")
+            .description("Bunu sadece doğrulanma formlarını inceleyen yetkililer yapabilir.")
+            .color(Color::Failure.into())
+            .build();
+
+        self.create_response(&InteractionResponse {
+            kind: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(
+                InteractionResponseDataBuilder::new()
+                    .embeds([embed])
+                    .flags(MessageFlags::EPHEMERAL)
+                    .build(),
+            ),
+        })
+        .await
+    }
+
+    /// Runs the interaction if the user is allowed to use it, telling them
+    /// otherwise.
+    async fn run<T: RunInteraction>(self, interaction: Interaction, access: Access) -> Result<()> {
+        if access == Access::Reviewers && !self.core.is_reviewer(&interaction) {
+            warn!(
+                user_id = ?interaction.author_id(),
+                custom_id = T::CUSTOM_ID,
+                "user without a reviewer role tried to use a moderation interaction"
+            );
+            return self.respond_unauthorized(interaction.kind).await;
+        }
+
+        let run_interaction = Box::pin(T::new(interaction, self)).await?;
+        Box::pin(run_interaction.run()).await
+    }
+
+    /// Edits the message the interaction was responded with, or the message
+    /// of the component if the response was deferred with
+    /// [`Self::defer_update`].
+    pub async fn update_response(&self, embeds: &[Embed], components: &[Component]) -> Result<()> {
         self.core
             .interaction_client()
-            .create_response(self.id, &self.token, response)
+            .update_response(&self.token)
+            .embeds(Some(embeds))?
+            .components(Some(components))?
             .await?;
+
         Ok(())
     }
 }
 
 impl Context {
+    /// Runs the interaction, telling the user if it fails with an incident id
+    /// that's also logged so that the error can be found.
     pub async fn handle_interaction(self, interaction: Interaction) -> Result<()> {
-        let ctx = InteractionContext::new(self, &interaction);
+        let ctx = InteractionContext::new(self.clone(), &interaction);
+        let incident_id = incident_id(interaction.id);
+        let kind = interaction.kind;
 
-        let interaction_data = interaction.data.clone().ok_or_else(|| {
-            anyhow!(
-                "interaction data is not  `ApplicationCommand`, `MessageComponent`, \
-                 `ApplicationCommandAutocomplete` or `ModalSubmit`"
-            )
-        })?;
+        // boxed, as are the interactions' futures, since they're large and
+        // would otherwise all be part of the future handling the event
+        if let Err(err) = Box::pin(self.run_interaction(interaction)).await {
+            error!(?err, %incident_id, "couldn't handle interaction");
+
+            let respond_result = if kind == InteractionType::ApplicationCommandAutocomplete {
+                ctx.respond_no_choices().await
+            } else {
+                ctx.respond_error(&incident_id).await
+            };
+            if let Err(respond_err) = respond_result {
+                warn!(
+                    ?respond_err,
+                    %incident_id, "couldn't tell the user that the interaction failed"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the submission in the embed of a message posted before the
+    /// submissions were stored, with the message's id as its id so that it's
+    /// imported only once.
+    ///
+    /// It's saved only to the primary store since the mirrors, being written
+    /// by the bot before, already have it.
+    async fn import_legacy_submission(&self, message: &Message) -> Result<()> {
+        let submission_id = message.id.get();
+
+        // held until the submission is saved so that it's not imported twice
+        let _lock = self.submission_locks.lock(submission_id).await?;
+        if self.storage.has_submission(submission_id).await? {
+            return Ok(());
+        }
+
+        let embed = message
+            .embeds
+            .first()
+            .ok_or_else(|| anyhow!("legacy submission message has no embed"))?;
+        let mut embed_fields = embed.fields.iter();
+
+        let user_id = embed_fields
+            .next()
+            .ok_or_else(|| anyhow!("legacy submission embed doesnt have a field"))?
+            .value
+            .as_str()
+            .trim_start_matches("<@")
+            .trim_end_matches('>')
+            .parse()?;
+
+        let answers = embed_fields
+            .filter_map(|embed_field| {
+                let field = self
+                    .form
+                    .fields
+                    .iter()
+                    .find(|field| field.embed_title == embed_field.name)?;
+
+                Some(FormAnswer {
+                    custom_id: field.custom_id.clone(),
+                    value: embed_field.value.clone(),
+                })
+            })
+            .collect();
 
-        let custom_id = match interaction_data {
-            InteractionData::ApplicationCommand(data) => data.name,
-            InteractionData::MessageComponent(data) => data.custom_id,
-            InteractionData::ModalSubmit(data) => data.custom_id,
-            _ => bail!("unknown interaction data kind"),
+        let mut record = VerificationSubmissionRecord::new(
+            submission_id,
+            VerificationSubmission { answers, user_id },
+        )?;
+        record.created_at = message.timestamp;
+        record.message_id = Some(message.id);
+        self.storage.import(&record).await?;
+
+        info!(submission_id, "imported legacy verification submission");
+
+        Ok(())
+    }
+
+    pub fn interaction_client(&self) -> InteractionClient<'_> {
+        self.client.interaction(self.application_id)
+    }
+
+    /// Returns whether the user has one of the reviewer roles or can manage the
+    /// guild.
+    fn is_reviewer(&self, interaction: &Interaction) -> bool {
+        let Some(member) = interaction.member.as_ref() else {
+            return false;
         };
 
-        match custom_id.as_str() {
+        member
+            .permissions
+            .is_some_and(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            || member
+                .roles
+                .iter()
+                .any(|role_id| self.config.reviewer_role_ids.contains(role_id))
+    }
+
+    async fn run_interaction(self, interaction: Interaction) -> Result<()> {
+        let ctx = InteractionContext::new(self, &interaction);
+
+        let custom_id = interaction_custom_id(&interaction)?.to_owned();
+        let custom_id_name = custom_id
+            .split_once(CUSTOM_ID_SEPARATOR)
+            .map_or(custom_id.as_str(), |(name, _)| name);
+
+        match custom_id_name {
             ApproveVerification::CUSTOM_ID => {
-                ApproveVerification::new(interaction, ctx)
-                    .await?
-                    .run()
-                    .await?;
+                ctx.run::<ApproveVerification>(interaction, Access::Reviewers)
+                    .await
             }
             CreateVerificationMessage::CUSTOM_ID => {
-                CreateVerificationMessage::new(interaction, ctx)
-                    .await?
-                    .run()
-                    .await?;
+                ctx.run::<CreateVerificationMessage>(interaction, Access::Reviewers)
+                    .await
+            }
+            FlushOutbox::CUSTOM_ID => ctx.run::<FlushOutbox>(interaction, Access::Reviewers).await,
+            PendingSubmissionsPage::CUSTOM_ID => {
+                ctx.run::<PendingSubmissionsPage>(interaction, Access::Reviewers)
+                    .await
+            }
+            RejectVerification::CUSTOM_ID => {
+                ctx.run::<RejectVerification>(interaction, Access::Reviewers)
+                    .await
+            }
+            RejectVerificationModalSubmit::CUSTOM_ID => {
+                ctx.run::<RejectVerificationModalSubmit>(interaction, Access::Reviewers)
+                    .await
+            }
+            SearchSubmissions::CUSTOM_ID => {
+                ctx.run::<SearchSubmissions>(interaction, Access::Reviewers)
+                    .await
+            }
+            ShowOutbox::CUSTOM_ID => ctx.run::<ShowOutbox>(interaction, Access::Reviewers).await,
+            ShowPendingSubmissions::CUSTOM_ID => {
+                ctx.run::<ShowPendingSubmissions>(interaction, Access::Reviewers)
+                    .await
+            }
+            ShowSubmissionInfo::CUSTOM_ID => {
+                ctx.run::<ShowSubmissionInfo>(interaction, Access::Reviewers)
+                    .await
             }
             ShowVerificationModal::CUSTOM_ID => {
-                ShowVerificationModal::new(interaction, ctx)
-                    .await?
-                    .run()
-                    .await?;
+                ctx.run::<ShowVerificationModal>(interaction, Access::Everyone)
+                    .await
+            }
+            UnverifyMember::CUSTOM_ID => {
+                ctx.run::<UnverifyMember>(interaction, Access::Reviewers)
+                    .await
+            }
+            UserSubmissionInfo::CUSTOM_ID => {
+                ctx.run::<UserSubmissionInfo>(interaction, Access::Reviewers)
+                    .await
             }
             VerificationModalSubmit::CUSTOM_ID => {
-                VerificationModalSubmit::new(interaction, ctx)
-                    .await?
-                    .run()
-                    .await?;
+                ctx.run::<VerificationModalSubmit>(interaction, Access::Everyone)
+                    .await
+            }
+            VerifyMember::CUSTOM_ID => {
+                ctx.run::<VerifyMember>(interaction, Access::Reviewers)
+                    .await
             }
             _ => bail!("unknown interaction custom id: {custom_id}"),
         }
-
-        Ok(())
-    }
-
-    pub fn interaction_client(&self) -> InteractionClient<'_> {
-        self.client.interaction(self.application_id)
     }
 
     pub async fn set_commands(&self) -> Result<()> {
         self.interaction_client()
             .set_guild_commands(
                 self.config.guild_id,
-                &[CreateVerificationMessage::command()?],
+                &[
+                    CreateVerificationMessage::command()?,
+                    SearchSubmissions::command()?,
+                    ShowOutbox::command()?,
+                    ShowPendingSubmissions::command()?,
+                    ShowSubmissionInfo::command()?,
+                    UnverifyMember::command()?,
+                    UserSubmissionInfo::command()?,
+                    VerifyMember::command(&self.form)?,
+                ],
             )
             .await?;
 
         Ok(())
     }
 }
+
+pub fn custom_id_with_arg<T: Display>(custom_id: &str, arg: T) -> String {
+    format!("{custom_id}{CUSTOM_ID_SEPARATOR}{arg}")
+}
+
+pub fn custom_id_arg(interaction: &Interaction) -> Result<&str> {
+    interaction_custom_id(interaction)?
+        .split_once(CUSTOM_ID_SEPARATOR)
+        .map(|(_, arg)| arg)
+        .ok_or_else(|| anyhow!("interaction custom id has no argument"))
+}
+
+/// Returns the argument in the custom id of a submission's component, or the
+/// id of the component's message if the component was posted before the
+/// submissions were stored and so has no argument, since that's the id the
+/// submission is imported with.
+pub fn submission_arg(interaction: &Interaction) -> Result<String> {
+    if let Some((_, arg)) = interaction_custom_id(interaction)?.split_once(CUSTOM_ID_SEPARATOR) {
+        return Ok(arg.to_owned());
+    }
+
+    let message = interaction
+        .message
+        .as_ref()
+        .ok_or_else(|| anyhow!("legacy submission interaction has no message"))?;
+
+    Ok(message.id.to_string())
+}
+
+/// Returns the interaction's message if it's the message of a submission
+/// posted before the submissions were stored, which has to be imported before
+/// the submission is read.
+pub fn legacy_submission_message(interaction: &Interaction, submission_id: u64) -> Option<Message> {
+    interaction
+        .message
+        .clone()
+        .filter(|message| message.id.get() == submission_id)
+}
+
+/// Returns the data of the slash command or context menu interaction.
+pub fn command_data(interaction: Interaction) -> Result<Box<CommandData>> {
+    match interaction.data {
+        Some(InteractionData::ApplicationCommand(data)) => Ok(data),
+        _ => bail!("interaction data is not of kind application command"),
+    }
+}
+
+fn interaction_custom_id(interaction: &Interaction) -> Result<&str> {
+    let interaction_data = interaction.data.as_ref().ok_or_else(|| {
+        anyhow!(
+            "interaction data is not  `ApplicationCommand`, `MessageComponent`, \
+             `ApplicationCommandAutocomplete` or `ModalSubmit`"
+        )
+    })?;
+
+    Ok(match interaction_data {
+        InteractionData::ApplicationCommand(data) => data.name.as_str(),
+        InteractionData::MessageComponent(data) => data.custom_id.as_str(),
+        InteractionData::ModalSubmit(data) => data.custom_id.as_str(),
+        _ => bail!("unknown interaction data kind"),
+    })
+}
+
+/// Returns a short id for the interaction to be shown to the user and logged
+/// when it fails.
+fn incident_id(interaction_id: Id<InteractionMarker>) -> String {
+    // the lower bits of the snowflake change with every interaction
+    format!("{:08X}", interaction_id.get() & 0xFFFF_FFFF)
+}
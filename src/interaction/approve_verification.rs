@@ -1,26 +1,265 @@
-use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Error, Result};
+use tracing::warn;
 use twilight_model::{
     application::interaction::Interaction,
-    channel::message::Embed,
-    http::interaction::{InteractionResponse, InteractionResponseType},
+    channel::{
+        message::{
+            component::{ActionRow, Button, ButtonStyle},
+            Component, ReactionType,
+        },
+        Message,
+    },
     id::{
         marker::{GuildMarker, UserMarker},
         Id,
     },
 };
-use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
 
 use crate::{
     color::Color,
-    interaction::{InteractionContext, RunInteraction},
+    interaction::{
+        custom_id_with_arg, legacy_submission_message,
+        reject_verification::reject_button,
+        submission_arg,
+        verification_modal_submit::{submission_embed, with_reviewer},
+        InteractionContext, RunInteraction, CUSTOM_ID_SEPARATOR,
+    },
+    model::{
+        audit::AuditAction,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+    name,
+    notification::render_template,
+    Context,
 };
 
+const MAX_STEP_ERROR_LENGTH: usize = 200;
+
+/// A part of the approval that can fail on its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApprovalStep {
+    /// Only done after all the other steps succeed, so that the user isn't
+    /// told they're verified before they are.
+    Dm,
+    Nickname,
+    Role,
+    Store,
+}
+
+impl ApprovalStep {
+    pub const ALL: [Self; 4] = [Self::Nickname, Self::Role, Self::Store, Self::Dm];
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Dm => "dm",
+            Self::Nickname => "nickname",
+            Self::Role => "role",
+            Self::Store => "store",
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Nickname => "İsim formdaki isim soyisme ayarlandı",
+            Self::Role => "Doğrulandı rolü verildi",
+            Self::Store => "Form kaydedildi",
+            Self::Dm => "Kullanıcıya DM'den iletildi",
+        }
+    }
+}
+
+impl FromStr for ApprovalStep {
+    type Err = Error;
+
+    fn from_str(step: &str) -> Result<Self> {
+        Ok(match step {
+            "nickname" => Self::Nickname,
+            "role" => Self::Role,
+            "store" => Self::Store,
+            "dm" => Self::Dm,
+            _ => bail!("unknown approval step: {step}"),
+        })
+    }
+}
+
+enum StepOutcome {
+    Done,
+    Failed(String),
+    /// Not done because an earlier step failed.
+    Skipped,
+}
+
+/// Approves a submission, or retries the steps of an approval that failed if
+/// the custom id has them after the submission id.
 pub struct ApproveVerification {
     ctx: InteractionContext,
-    submission_embed: Embed,
     guild_id: Id<GuildMarker>,
-    name_surname: String,
-    user_id: Id<UserMarker>,
+    /// The message of the submission if it has to be imported first.
+    legacy_message: Option<Message>,
+    /// The steps that are retried, `None` if this is the first approval.
+    retried_steps: Option<Vec<ApprovalStep>>,
+    reviewer_id: Id<UserMarker>,
+    reviewer_name: String,
+    submission_id: u64,
+}
+
+/// The moderator approving a submission, used to run the approval steps
+/// both for the submitted forms and the ones filled in by moderators.
+pub struct Approval<'a> {
+    pub ctx: &'a Context,
+    pub guild_id: Id<GuildMarker>,
+    pub reviewer_id: Id<UserMarker>,
+    pub reviewer_name: &'a str,
+}
+
+impl Approval<'_> {
+    async fn notify_user(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        let embed = EmbedBuilder::new()
+            .title("✅ Doğrulandınız")
+            .description(render_template(
+                &self.ctx.config.approval_dm_template,
+                record,
+                None,
+            ))
+            .color(Color::Success.into())
+            .build();
+
+        self.ctx.send_dm(record.submission.user_id, embed).await
+    }
+
+    /// Runs each step on its own, returning the submission embed with the
+    /// outcome of the steps and a button to retry the ones that failed.
+    pub async fn run(
+        &self,
+        steps: &[ApprovalStep],
+        record: &mut VerificationSubmissionRecord,
+    ) -> (EmbedBuilder, Vec<Component>) {
+        let mut checklist = vec![];
+        let mut retried_steps = vec![];
+        // whether a step other than the dm failed
+        let mut any_failed = false;
+        for step in ApprovalStep::ALL {
+            let outcome = if !steps.contains(&step) {
+                StepOutcome::Done
+            } else if step == ApprovalStep::Dm && any_failed {
+                StepOutcome::Skipped
+            } else {
+                match self.run_step(step, record).await {
+                    Ok(()) => StepOutcome::Done,
+                    Err(err) => {
+                        warn!(?err, ?step, "couldn't run verification approval step");
+                        any_failed |= step != ApprovalStep::Dm;
+                        StepOutcome::Failed(err.to_string())
+                    }
+                }
+            };
+            if !matches!(outcome, StepOutcome::Done) {
+                retried_steps.push(step.as_str());
+            }
+            checklist.push(match outcome {
+                StepOutcome::Done => format!("✅ {}", step.label()),
+                StepOutcome::Failed(err) => format!(
+                    "❌ {}: {}",
+                    step.label(),
+                    err.chars().take(MAX_STEP_ERROR_LENGTH).collect::<String>()
+                ),
+                StepOutcome::Skipped => format!("⏸️ {}", step.label()),
+            });
+        }
+
+        // the user is verified even if they couldn't be notified
+        let (title, color) = if any_failed {
+            (
+                "⚠️ Kullanıcı doğrulanırken bazı adımlar başarısız oldu",
+                Color::Pending,
+            )
+        } else {
+            ("✅ Kullanıcı doğrulandı", Color::Success)
+        };
+        let reviewer_name =
+            (record.reviewer_id == Some(self.reviewer_id)).then_some(self.reviewer_name);
+        let embed = with_reviewer(
+            submission_embed(&self.ctx.form, &record.submission)
+                .field(EmbedFieldBuilder::new("Adımlar", checklist.join("\n")))
+                .title(title)
+                .color(color.into()),
+            record,
+            reviewer_name,
+        );
+
+        let components = if retried_steps.is_empty() {
+            vec![]
+        } else {
+            let mut buttons = vec![Component::Button(Button {
+                custom_id: Some(custom_id_with_arg(
+                    ApproveVerification::CUSTOM_ID,
+                    format!(
+                        "{}{CUSTOM_ID_SEPARATOR}{}",
+                        record.id,
+                        retried_steps.join(",")
+                    ),
+                )),
+                disabled: false,
+                emoji: Some(ReactionType::Unicode {
+                    name: "🔁".to_owned(),
+                }),
+                label: Some("Başarısız Adımları Tekrar Dene".to_owned()),
+                style: ButtonStyle::Primary,
+                url: None,
+            })];
+            // the submission can still be rejected if it couldn't be saved as
+            // approved
+            if record.status == VerificationStatus::Pending {
+                buttons.push(reject_button(record.id));
+            }
+
+            vec![Component::ActionRow(ActionRow {
+                components: buttons,
+            })]
+        };
+
+        (embed, components)
+    }
+
+    async fn run_step(
+        &self,
+        step: ApprovalStep,
+        record: &mut VerificationSubmissionRecord,
+    ) -> Result<()> {
+        let user_id = record.submission.user_id;
+
+        match step {
+            ApprovalStep::Nickname => {
+                self.ctx
+                    .client
+                    .update_guild_member(self.guild_id, user_id)
+                    .nick(Some(&name::nickname(record.submission.name_surname())))?
+                    .await?;
+            }
+            ApprovalStep::Role => {
+                self.ctx
+                    .client
+                    .add_guild_member_role(self.guild_id, user_id, self.ctx.config.verified_role_id)
+                    .await?;
+            }
+            ApprovalStep::Store => {
+                let mut reviewed_record = record.clone();
+                reviewed_record.review(VerificationStatus::Approved, None, self.reviewer_id)?;
+                self.ctx.storage.update(&reviewed_record).await?;
+                *record = reviewed_record;
+
+                self.ctx
+                    .storage
+                    .add_audit_log_entry(record, AuditAction::Approve)?;
+            }
+            ApprovalStep::Dm => self.notify_user(record).await?,
+        }
+
+        Ok(())
+    }
 }
 
 impl RunInteraction for ApproveVerification {
@@ -31,76 +270,78 @@ impl RunInteraction for ApproveVerification {
             .guild_id
             .ok_or_else(|| anyhow!("approve verification interaction doesnt have a guild id"))?;
 
-        let message = interaction
-            .message
-            .ok_or_else(|| anyhow!("approve verification interaction has no message"))?;
-        let submission_embed = message
-            .embeds
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow!("approve verification interaction has no embed"))?;
-        let mut embed_fields = submission_embed.fields.iter();
-
-        let user_id = embed_fields
-            .next()
-            .ok_or_else(|| anyhow!("submissiom embed doesnt have a field"))?
-            .value
-            .as_str()
-            .trim_start_matches("<@")
-            .trim_end_matches('>')
-            .parse()?;
-
-        let name_surname = embed_fields
-            .next()
-            .ok_or_else(|| anyhow!("submission embed has only one field"))?
-            .value
-            .clone();
+        let reviewer = interaction
+            .author()
+            .ok_or_else(|| anyhow!("approve verification interaction has no user"))?;
+        let reviewer_id = reviewer.id;
+        let reviewer_name = reviewer.name.clone();
+
+        let arg = submission_arg(&interaction)?;
+        let (submission_id, retried_steps) = match arg.split_once(CUSTOM_ID_SEPARATOR) {
+            Some((submission_id, steps)) => (
+                submission_id.parse()?,
+                Some(
+                    steps
+                        .split(',')
+                        .map(str::parse)
+                        .collect::<Result<Vec<_>>>()?,
+                ),
+            ),
+            None => (arg.parse()?, None),
+        };
 
         Ok(Self {
             ctx,
-            submission_embed,
             guild_id,
-            name_surname,
-            user_id,
+            legacy_message: legacy_submission_message(&interaction, submission_id),
+            reviewer_id,
+            reviewer_name,
+            retried_steps,
+            submission_id,
         })
     }
 
     async fn run(self) -> Result<()> {
-        self.ctx
-            .core
-            .client
-            .update_guild_member(self.guild_id, self.user_id)
-            .nick(Some(&self.name_surname))?
-            .await?;
+        self.ctx.defer_update().await?;
 
-        self.ctx
-            .core
-            .client
-            .add_guild_member_role(
-                self.guild_id,
-                self.user_id,
-                self.ctx.core.config.verified_role_id,
-            )
-            .await?;
+        if let Some(message) = self.legacy_message.as_ref() {
+            self.ctx.core.import_legacy_submission(message).await?;
+        }
 
-        self.ctx
+        // held until the review is done so that no one else reviews the
+        // submission meanwhile
+        let _lock = self
+            .ctx
             .core
-            .sheets
-            .set_verification_submission_approved(self.user_id)
+            .submission_locks
+            .lock(self.submission_id)
             .await?;
+        let mut record = self.ctx.core.storage.submission(self.submission_id).await?;
+
+        let steps = match self.retried_steps.as_ref() {
+            None if record.status == VerificationStatus::Pending => ApprovalStep::ALL.to_vec(),
+            // the submission isn't approved if saving it failed
+            Some(steps)
+                if record.status == VerificationStatus::Approved
+                    || (record.status == VerificationStatus::Pending
+                        && steps.contains(&ApprovalStep::Store)) =>
+            {
+                steps.clone()
+            }
+            _ => return self.ctx.respond_already_reviewed(&record).await,
+        };
 
-        let mut embed = self.submission_embed.clone();
-        embed.title = Some("✅ Kullanıcı doğrulandı".to_owned());
-        embed.color = Some(Color::Success.into());
-        let response = InteractionResponseDataBuilder::new()
-            .embeds([embed])
-            .components([]);
+        let (embed, components) = Approval {
+            ctx: &self.ctx.core,
+            guild_id: self.guild_id,
+            reviewer_id: self.reviewer_id,
+            reviewer_name: &self.reviewer_name,
+        }
+        .run(&steps, &mut record)
+        .await;
 
         self.ctx
-            .create_response(&InteractionResponse {
-                kind: InteractionResponseType::UpdateMessage,
-                data: Some(response.build()),
-            })
+            .update_response(&[embed.build()], &components)
             .await?;
 
         Ok(())
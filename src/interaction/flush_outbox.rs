@@ -0,0 +1,32 @@
+use anyhow::Result;
+use twilight_model::application::interaction::Interaction;
+
+use crate::interaction::{
+    show_outbox::{outbox_components, outbox_embed},
+    InteractionContext, RunInteraction,
+};
+
+pub struct FlushOutbox {
+    ctx: InteractionContext,
+}
+
+impl RunInteraction for FlushOutbox {
+    const CUSTOM_ID: &'static str = "flush-outbox";
+
+    async fn new(_: Interaction, ctx: InteractionContext) -> Result<Self> {
+        Ok(Self { ctx })
+    }
+
+    async fn run(self) -> Result<()> {
+        self.ctx.defer_update().await?;
+
+        self.ctx.core.storage.retry_outbox(true).await?;
+        let entries = self.ctx.core.storage.outbox()?;
+
+        self.ctx
+            .update_response(&[outbox_embed(&entries)?], &outbox_components(&entries))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use twilight_model::application::interaction::Interaction;
+
+use crate::interaction::{
+    custom_id_arg, show_pending_submissions::pending_submissions_page, InteractionContext,
+    RunInteraction,
+};
+
+pub struct PendingSubmissionsPage {
+    ctx: InteractionContext,
+    page: usize,
+}
+
+impl RunInteraction for PendingSubmissionsPage {
+    const CUSTOM_ID: &'static str = "pending-submissions-page";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        Ok(Self {
+            page: custom_id_arg(&interaction)?.parse()?,
+            ctx,
+        })
+    }
+
+    async fn run(self) -> Result<()> {
+        self.ctx.defer_update().await?;
+
+        let (embed, components) = pending_submissions_page(&self.ctx.core, self.page).await?;
+        self.ctx.update_response(&[embed], &components).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use twilight_model::{
+    application::interaction::Interaction,
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle, TextInput, TextInputStyle},
+        Component, ReactionType,
+    },
+    http::interaction::{InteractionResponse, InteractionResponseType},
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
+
+use crate::interaction::{
+    custom_id_with_arg, reject_verification_modal_submit::RejectVerificationModalSubmit,
+    submission_arg, InteractionContext, RunInteraction,
+};
+
+pub struct RejectVerification {
+    ctx: InteractionContext,
+    id: u64,
+}
+
+impl RunInteraction for RejectVerification {
+    const CUSTOM_ID: &'static str = "reject-verification";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        Ok(Self {
+            id: submission_arg(&interaction)?.parse()?,
+            ctx,
+        })
+    }
+
+    async fn run(self) -> Result<()> {
+        let reason_input = Component::TextInput(TextInput {
+            custom_id: "reason".to_owned(),
+            label: "REDDETME SEBEBİ".to_owned(),
+            max_length: Some(1000),
+            min_length: None,
+            placeholder: Some("Bu sebep kullanıcıya da iletilecek.".to_owned()),
+            required: None,
+            style: TextInputStyle::Paragraph,
+            value: None,
+        });
+
+        let response = InteractionResponseDataBuilder::new()
+            .custom_id(custom_id_with_arg(
+                RejectVerificationModalSubmit::CUSTOM_ID,
+                self.id,
+            ))
+            .title("❌ Doğrulanma Formunu Reddet")
+            .components([Component::ActionRow(ActionRow {
+                components: vec![reason_input],
+            })])
+            .build();
+
+        self.ctx
+            .create_response(&InteractionResponse {
+                kind: InteractionResponseType::Modal,
+                data: Some(response),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Returns the button that rejects the submission.
+pub fn reject_button(submission_id: u64) -> Component {
+    Component::Button(Button {
+        custom_id: Some(custom_id_with_arg(
+            RejectVerification::CUSTOM_ID,
+            submission_id,
+        )),
+        disabled: false,
+        emoji: Some(ReactionType::Unicode {
+            name: "❌".to_owned(),
+        }),
+        label: Some("Reddet".to_owned()),
+        style: ButtonStyle::Danger,
+        url: None,
+    })
+}
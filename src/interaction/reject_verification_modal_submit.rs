@@ -0,0 +1,145 @@
+use anyhow::{anyhow, bail, Result};
+use tracing::warn;
+use twilight_model::{
+    application::interaction::{Interaction, InteractionData},
+    channel::Message,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+use crate::{
+    color::Color,
+    interaction::{
+        legacy_submission_message, submission_arg,
+        verification_modal_submit::{submission_embed, with_reviewer},
+        InteractionContext, RunInteraction,
+    },
+    model::{
+        audit::AuditAction,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+    notification::{dm_status_field, render_template},
+};
+
+pub struct RejectVerificationModalSubmit {
+    ctx: InteractionContext,
+    /// The message of the submission if it has to be imported first.
+    legacy_message: Option<Message>,
+    reason: String,
+    reviewer_id: Id<UserMarker>,
+    reviewer_name: String,
+    submission_id: u64,
+}
+
+impl RejectVerificationModalSubmit {
+    async fn notify_user(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        let embed = EmbedBuilder::new()
+            .title("❌ Doğrulanma formunuz reddedildi")
+            .description(render_template(
+                &self.ctx.core.config.rejection_dm_template,
+                record,
+                Some(&self.reason),
+            ))
+            .color(Color::Failure.into())
+            .build();
+
+        self.ctx
+            .core
+            .send_dm(record.submission.user_id, embed)
+            .await
+    }
+}
+
+impl RunInteraction for RejectVerificationModalSubmit {
+    const CUSTOM_ID: &'static str = "reject-verification-modal-submit";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let submission_id = submission_arg(&interaction)?.parse()?;
+        let legacy_message = legacy_submission_message(&interaction, submission_id);
+
+        let reviewer = interaction
+            .author()
+            .ok_or_else(|| anyhow!("reject verification modal interaction has no user"))?;
+        let reviewer_id = reviewer.id;
+        let reviewer_name = reviewer.name.clone();
+
+        let InteractionData::ModalSubmit(modal) = interaction
+            .data
+            .ok_or_else(|| anyhow!("reject verification modal has no interaction data"))?
+        else {
+            bail!("reject verification modal data is not of kind modal submit")
+        };
+
+        let reason = modal
+            .components
+            .into_iter()
+            .next()
+            .and_then(|row| row.components.into_iter().next())
+            .and_then(|component| component.value)
+            .ok_or_else(|| anyhow!("reject verification modal has no reason"))?;
+
+        Ok(Self {
+            ctx,
+            legacy_message,
+            reason,
+            reviewer_id,
+            reviewer_name,
+            submission_id,
+        })
+    }
+
+    async fn run(self) -> Result<()> {
+        self.ctx.defer_update().await?;
+
+        if let Some(message) = self.legacy_message.as_ref() {
+            self.ctx.core.import_legacy_submission(message).await?;
+        }
+
+        // held until the review is done so that no one else reviews the
+        // submission meanwhile
+        let _lock = self
+            .ctx
+            .core
+            .submission_locks
+            .lock(self.submission_id)
+            .await?;
+        let mut record = self.ctx.core.storage.submission(self.submission_id).await?;
+        if record.status != VerificationStatus::Pending {
+            return self.ctx.respond_already_reviewed(&record).await;
+        }
+
+        record.review(
+            VerificationStatus::Rejected,
+            Some(self.reason.clone()),
+            self.reviewer_id,
+        )?;
+        self.ctx.core.storage.update(&record).await?;
+        self.ctx
+            .core
+            .storage
+            .add_audit_log_entry(&record, AuditAction::Reject)?;
+
+        let dm_delivered = match self.notify_user(&record).await {
+            Ok(()) => true,
+            Err(err) => {
+                warn!(?err, "couldn't notify user of verification rejection");
+                false
+            }
+        };
+
+        let embed = with_reviewer(
+            submission_embed(&self.ctx.core.form, &record.submission)
+                .title("❌ Kullanıcı reddedildi")
+                .field(EmbedFieldBuilder::new("Reddetme Sebebi", &self.reason))
+                .field(dm_status_field(dm_delivered))
+                .color(Color::Failure.into()),
+            &record,
+            Some(&self.reviewer_name),
+        )
+        .build();
+
+        self.ctx.update_response(&[embed], &[]).await?;
+
+        Ok(())
+    }
+}
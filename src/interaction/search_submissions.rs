@@ -0,0 +1,235 @@
+use std::{collections::HashSet, fmt::Write as _};
+
+use anyhow::{anyhow, Result};
+use tracing::warn;
+use twilight_model::{
+    application::{
+        command::{Command, CommandOptionChoice, CommandOptionChoiceValue, CommandType},
+        interaction::{application_command::CommandOptionValue, Interaction, InteractionType},
+    },
+    guild::Permissions,
+    http::interaction::{InteractionResponse, InteractionResponseType},
+};
+use twilight_util::builder::{
+    command::{CommandBuilder, StringBuilder},
+    embed::EmbedBuilder,
+    InteractionResponseDataBuilder,
+};
+
+use crate::{
+    color::Color,
+    interaction::{command_data, CreateCommand, InteractionContext, RunInteraction},
+    model::verification::VerificationSubmissionRecord,
+    name, Context,
+};
+
+const QUERY_OPTION: &str = "arama";
+/// The most choices Discord accepts in an autocomplete response.
+const MAX_CHOICES: usize = 25;
+const MAX_CHOICE_NAME_LENGTH: usize = 100;
+const MAX_LISTED_RESULTS: usize = 15;
+
+/// Searches the submissions by the answers to the searchable fields of the
+/// form, suggesting the matches while the query is typed.
+pub struct SearchSubmissions {
+    ctx: InteractionContext,
+    /// Whether the user is still typing the query, in which case the matches
+    /// are suggested instead of listed.
+    is_autocomplete: bool,
+    query: String,
+}
+
+impl SearchSubmissions {
+    async fn respond_autocomplete(self) -> Result<()> {
+        // the autocomplete has to be answered quickly, so only the search
+        // index is used and nothing is suggested if it can't be
+        let submissions = match self.ctx.core.storage.indexed_submissions() {
+            Ok(submissions) => submissions.unwrap_or_default(),
+            Err(err) => {
+                warn!(?err, "couldn't read search index");
+                vec![]
+            }
+        };
+
+        let choices = search(&self.ctx.core, submissions, &self.query)
+            .iter()
+            .take(MAX_CHOICES)
+            .map(|record| CommandOptionChoice {
+                name: record_summary(&self.ctx.core, record)
+                    .chars()
+                    .take(MAX_CHOICE_NAME_LENGTH)
+                    .collect(),
+                name_localizations: None,
+                // the exact submission is shown if a suggestion is picked
+                value: CommandOptionChoiceValue::String(record.submission.user_id.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        self.ctx
+            .create_response(&InteractionResponse {
+                kind: InteractionResponseType::ApplicationCommandAutocompleteResult,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .choices(choices)
+                        .build(),
+                ),
+            })
+            .await
+    }
+}
+
+impl CreateCommand for SearchSubmissions {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "Doğrulanma formlarında isim, e-posta veya kuruma göre ara",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(
+            StringBuilder::new(
+                QUERY_OPTION,
+                "Aranacak isim, e-posta veya kurumun bir kısmı",
+            )
+            .autocomplete(true)
+            .required(true),
+        )
+        .validate()?
+        .build())
+    }
+}
+
+impl RunInteraction for SearchSubmissions {
+    const CUSTOM_ID: &'static str = "doğrulama_ara";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let is_autocomplete = interaction.kind == InteractionType::ApplicationCommandAutocomplete;
+
+        let query = command_data(interaction)?
+            .options
+            .into_iter()
+            .find_map(|option| match option.value {
+                CommandOptionValue::String(query) | CommandOptionValue::Focused(query, _)
+                    if option.name == QUERY_OPTION =>
+                {
+                    Some(query)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("search submissions command has no query"))?;
+
+        Ok(Self {
+            ctx,
+            is_autocomplete,
+            query,
+        })
+    }
+
+    async fn run(self) -> Result<()> {
+        if self.is_autocomplete {
+            return self.respond_autocomplete().await;
+        }
+
+        // loading the search index can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        let submissions = match self.ctx.core.storage.indexed_submissions()? {
+            Some(submissions) => submissions,
+            None => self.ctx.core.storage.refresh_search_index().await?,
+        };
+        let results = search(&self.ctx.core, submissions, &self.query);
+        if results.is_empty() {
+            let embed = EmbedBuilder::new()
+                .title("🔍 Sonuç bulunamadı")
+                .description(format!(
+                    "`{}` ile eşleşen bir form yok.",
+                    self.query.escape_debug()
+                ))
+                .color(Color::Pending.into())
+                .build();
+
+            return self.ctx.followup(&[embed], true).await;
+        }
+
+        let mut description = String::new();
+        for record in results.iter().take(MAX_LISTED_RESULTS) {
+            writeln!(
+                description,
+                "- <@{}> {}",
+                record.submission.user_id,
+                record_summary(&self.ctx.core, record)
+            )?;
+        }
+        let remaining = results.len().saturating_sub(MAX_LISTED_RESULTS);
+        if remaining > 0 {
+            writeln!(
+                description,
+                "\nve {remaining} kullanıcı daha, aramayı daraltın"
+            )?;
+        }
+
+        let embed = EmbedBuilder::new()
+            .title(format!("🔍 {} kullanıcı bulundu", results.len()))
+            .description(description)
+            .color(Color::Success.into())
+            .build();
+
+        self.ctx.followup(&[embed], true).await
+    }
+}
+
+/// Returns the latest submission of each user whose answer to a searchable
+/// field contains the query, or whose id is the query, newest first, the
+/// submissions should be sorted oldest first.
+fn search(
+    ctx: &Context,
+    submissions: Vec<VerificationSubmissionRecord>,
+    raw_query: &str,
+) -> Vec<VerificationSubmissionRecord> {
+    let query = name::to_lowercase(raw_query.trim());
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let mut seen_user_ids = HashSet::new();
+    let mut results = vec![];
+    for record in submissions.into_iter().rev() {
+        if !seen_user_ids.insert(record.submission.user_id) {
+            continue;
+        }
+
+        let matches = record.submission.user_id.to_string() == query
+            || ctx
+                .form
+                .fields
+                .iter()
+                .filter(|field| field.searchable)
+                .filter_map(|field| record.submission.answer(&field.custom_id))
+                .any(|answer| name::to_lowercase(answer).contains(&query));
+        if matches {
+            results.push(record);
+        }
+    }
+
+    results
+}
+
+/// Returns the answers to the searchable fields and the status of the
+/// submission.
+fn record_summary(ctx: &Context, record: &VerificationSubmissionRecord) -> String {
+    let mut summary = ctx
+        .form
+        .fields
+        .iter()
+        .filter(|field| field.searchable)
+        .filter_map(|field| record.submission.answer(&field.custom_id))
+        .filter(|answer| !answer.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ");
+    if summary.is_empty() {
+        summary = record.submission.user_id.to_string();
+    }
+
+    format!("{summary} ({})", record.status.label())
+}
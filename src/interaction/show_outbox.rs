@@ -0,0 +1,130 @@
+use std::fmt::Write as _;
+
+use anyhow::Result;
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::Interaction,
+    },
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component, Embed, MessageFlags, ReactionType,
+    },
+    guild::Permissions,
+    http::interaction::{InteractionResponse, InteractionResponseType},
+};
+use twilight_util::builder::{
+    command::CommandBuilder, embed::EmbedBuilder, InteractionResponseDataBuilder,
+};
+
+use crate::{
+    color::Color,
+    interaction::{flush_outbox::FlushOutbox, CreateCommand, InteractionContext, RunInteraction},
+    model::outbox::OutboxEntry,
+};
+
+const MAX_LISTED_ENTRIES: usize = 15;
+const MAX_ERROR_LEN: usize = 100;
+
+pub struct ShowOutbox {
+    ctx: InteractionContext,
+}
+
+impl CreateCommand for ShowOutbox {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "Kaydedilemeyip tekrar denenecek işlemleri göster",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .validate()?
+        .build())
+    }
+}
+
+impl RunInteraction for ShowOutbox {
+    const CUSTOM_ID: &'static str = "kayıt_kuyruğu";
+
+    async fn new(_: Interaction, ctx: InteractionContext) -> Result<Self> {
+        Ok(Self { ctx })
+    }
+
+    async fn run(self) -> Result<()> {
+        let entries = self.ctx.core.storage.outbox()?;
+
+        self.ctx
+            .create_response(&InteractionResponse {
+                kind: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(
+                    InteractionResponseDataBuilder::new()
+                        .embeds([outbox_embed(&entries)?])
+                        .components(outbox_components(&entries))
+                        .flags(MessageFlags::EPHEMERAL)
+                        .build(),
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+pub fn outbox_embed(entries: &[OutboxEntry]) -> Result<Embed> {
+    if entries.is_empty() {
+        return Ok(EmbedBuilder::new()
+            .title("✅ Kayıt kuyruğu boş")
+            .description("Bütün formlar her yere kaydedildi.")
+            .color(Color::Success.into())
+            .build());
+    }
+
+    let mut description = String::new();
+    for entry in entries.iter().take(MAX_LISTED_ENTRIES) {
+        writeln!(
+            description,
+            "- **{}** `{}` formu ({}): ilk olarak <t:{}:R>, {} kez denendi, sonraki deneme \
+             <t:{}:R>\n  > {}",
+            entry.store,
+            entry.submission_id,
+            entry.operation.label(),
+            entry.created_at.as_secs(),
+            entry.attempts,
+            entry.next_attempt_at.as_secs(),
+            entry
+                .last_error
+                .chars()
+                .take(MAX_ERROR_LEN)
+                .collect::<String>(),
+        )?;
+    }
+    let remaining = entries.len().saturating_sub(MAX_LISTED_ENTRIES);
+    if remaining > 0 {
+        writeln!(description, "\nve {remaining} işlem daha")?;
+    }
+
+    Ok(EmbedBuilder::new()
+        .title(format!("⏳ {} işlem kaydedilmeyi bekliyor", entries.len()))
+        .description(description)
+        .color(Color::Pending.into())
+        .build())
+}
+
+pub fn outbox_components(entries: &[OutboxEntry]) -> Vec<Component> {
+    if entries.is_empty() {
+        return vec![];
+    }
+
+    vec![Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(FlushOutbox::CUSTOM_ID.to_owned()),
+            disabled: false,
+            emoji: Some(ReactionType::Unicode {
+                name: "🔁".to_owned(),
+            }),
+            label: Some("Hepsini Şimdi Dene".to_owned()),
+            style: ButtonStyle::Primary,
+            url: None,
+        })],
+    })]
+}
@@ -0,0 +1,180 @@
+use std::fmt::Write as _;
+
+use anyhow::Result;
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::Interaction,
+    },
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component, Embed, ReactionType,
+    },
+    guild::Permissions,
+};
+use twilight_util::builder::{
+    command::CommandBuilder,
+    embed::{EmbedBuilder, EmbedFooterBuilder},
+};
+
+use crate::{
+    color::Color,
+    interaction::{
+        custom_id_with_arg, pending_submissions_page::PendingSubmissionsPage, CreateCommand,
+        InteractionContext, RunInteraction,
+    },
+    model::verification::{VerificationStatus, VerificationSubmissionRecord},
+    Context,
+};
+
+const PAGE_SIZE: usize = 10;
+
+pub struct ShowPendingSubmissions {
+    ctx: InteractionContext,
+}
+
+impl CreateCommand for ShowPendingSubmissions {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "İncelenmeyi bekleyen doğrulanma formlarını göster",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .validate()?
+        .build())
+    }
+}
+
+impl RunInteraction for ShowPendingSubmissions {
+    const CUSTOM_ID: &'static str = "bekleyen_doğrulamalar";
+
+    async fn new(_: Interaction, ctx: InteractionContext) -> Result<Self> {
+        Ok(Self { ctx })
+    }
+
+    async fn run(self) -> Result<()> {
+        // reading the submissions can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        let (embed, components) = pending_submissions_page(&self.ctx.core, 0).await?;
+        self.ctx.update_response(&[embed], &components).await?;
+
+        Ok(())
+    }
+}
+
+/// Returns the embed listing the pending submissions on the given page, oldest
+/// first, and the buttons to switch pages.
+///
+/// The page is clamped to the last page since submissions might have been
+/// reviewed since the previous page was shown.
+pub async fn pending_submissions_page(
+    ctx: &Context,
+    requested_page: usize,
+) -> Result<(Embed, Vec<Component>)> {
+    let mut submissions = ctx
+        .storage
+        .submissions()
+        .await?
+        .into_iter()
+        .filter(|record| record.status == VerificationStatus::Pending)
+        .collect::<Vec<_>>();
+    submissions.sort_by_key(|record| (record.created_at.as_secs(), record.id));
+
+    if submissions.is_empty() {
+        return Ok((
+            EmbedBuilder::new()
+                .title("✅ Bekleyen doğrulanma formu yok")
+                .description("Bütün formlar incelendi.")
+                .color(Color::Success.into())
+                .build(),
+            vec![],
+        ));
+    }
+
+    let page_count = submissions.len().div_ceil(PAGE_SIZE);
+    let page = requested_page.min(page_count.saturating_sub(1));
+
+    let mut description = String::new();
+    for record in submissions
+        .iter()
+        .skip(page.saturating_mul(PAGE_SIZE))
+        .take(PAGE_SIZE)
+    {
+        writeln!(description, "{}", pending_submission_line(ctx, record)?)?;
+    }
+
+    let embed = EmbedBuilder::new()
+        .title(format!(
+            "⏳ {} doğrulanma formu incelenmeyi bekliyor",
+            submissions.len()
+        ))
+        .description(description)
+        .footer(EmbedFooterBuilder::new(format!(
+            "Sayfa {}/{page_count}",
+            page.saturating_add(1)
+        )))
+        .color(Color::Pending.into())
+        .build();
+
+    let page_button = |target_page: usize, emoji: &str, label: &str| {
+        Component::Button(Button {
+            custom_id: Some(custom_id_with_arg(
+                PendingSubmissionsPage::CUSTOM_ID,
+                target_page,
+            )),
+            disabled: false,
+            emoji: Some(ReactionType::Unicode {
+                name: emoji.to_owned(),
+            }),
+            label: Some(label.to_owned()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        })
+    };
+
+    // the buttons that would go out of the pages are left out instead of
+    // disabled since the custom ids of the buttons have to be different
+    let mut buttons = vec![];
+    if let Some(previous_page) = page.checked_sub(1) {
+        buttons.push(page_button(previous_page, "◀️", "Önceki"));
+    }
+    buttons.push(page_button(page, "🔄", "Yenile"));
+    let next_page = page.saturating_add(1);
+    if next_page < page_count {
+        buttons.push(page_button(next_page, "▶️", "Sonraki"));
+    }
+
+    Ok((
+        embed,
+        vec![Component::ActionRow(ActionRow {
+            components: buttons,
+        })],
+    ))
+}
+
+/// Returns the line listing the submission with who sent it, when and the link
+/// to its message.
+pub fn pending_submission_line(
+    ctx: &Context,
+    record: &VerificationSubmissionRecord,
+) -> Result<String> {
+    let mut line = format!(
+        "- <@{}> **{}**, <t:{}:R> gönderildi",
+        record.submission.user_id,
+        record.submission.name_surname(),
+        record.created_at.as_secs()
+    );
+
+    if let Some(message_id) = record.message_id {
+        write!(
+            line,
+            " · [Mesaja git](https://discord.com/channels/{}/{}/{message_id})",
+            ctx.config.guild_id, ctx.config.verification_submissions_channel_id
+        )?;
+    }
+
+    Ok(line)
+}
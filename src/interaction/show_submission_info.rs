@@ -0,0 +1,337 @@
+use std::fmt::Write as _;
+
+use anyhow::{anyhow, Result};
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::{application_command::CommandOptionValue, Interaction},
+    },
+    channel::message::Embed,
+    guild::Permissions,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::{
+    command::{CommandBuilder, UserBuilder},
+    embed::{EmbedBuilder, EmbedFieldBuilder},
+};
+
+use crate::{
+    color::Color,
+    interaction::{
+        command_data, verification_modal_submit::submission_embed, CreateCommand,
+        InteractionContext, RunInteraction,
+    },
+    model::{
+        audit::AuditLogEntry,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+    Context,
+};
+
+const USER_OPTION: &str = "kullanıcı";
+const MAX_LISTED_EARLIER_SUBMISSIONS: usize = 10;
+const MAX_LISTED_AUDIT_LOG_ENTRIES: usize = 10;
+/// The reasons in the history are cut so that more of its entries fit in the
+/// field.
+const MAX_LISTED_REASON_LENGTH: usize = 40;
+const MAX_FIELD_LENGTH: usize = 1024;
+
+/// Shows a member's submission to the moderators.
+pub struct ShowSubmissionInfo {
+    ctx: InteractionContext,
+    user_id: Id<UserMarker>,
+}
+
+impl CreateCommand for ShowSubmissionInfo {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "Bir kullanıcının doğrulanma formunu ve durumunu göster",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(UserBuilder::new(USER_OPTION, "Formu gösterilecek kullanıcı").required(true))
+        .validate()?
+        .build())
+    }
+}
+
+impl RunInteraction for ShowSubmissionInfo {
+    const CUSTOM_ID: &'static str = "doğrulama_bilgisi";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let user_id = command_data(interaction)?
+            .options
+            .into_iter()
+            .find_map(|option| match option.value {
+                CommandOptionValue::User(user_id) if option.name == USER_OPTION => Some(user_id),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("submission info command has no user"))?;
+
+        Ok(Self { ctx, user_id })
+    }
+
+    async fn run(self) -> Result<()> {
+        // reading the submissions can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        let embed = submission_info_embed(&self.ctx.core, self.user_id).await?;
+        self.ctx.followup(&[embed], true).await
+    }
+}
+
+/// Returns the embed with the user's latest submission, its status, who
+/// reviewed it and when, the statuses of their earlier submissions and the
+/// moderation actions taken on them.
+pub async fn submission_info_embed(ctx: &Context, user_id: Id<UserMarker>) -> Result<Embed> {
+    let mut submissions = ctx
+        .storage
+        .submissions()
+        .await?
+        .into_iter()
+        .filter(|record| record.submission.user_id == user_id)
+        .collect::<Vec<_>>();
+    submissions.sort_by_key(|record| record.id);
+
+    let Some(record) = submissions.pop() else {
+        return Ok(EmbedBuilder::new()
+            .title("🔍 Form bulunamadı")
+            .description(format!(
+                "<@{user_id}> henüz bir doğrulanma formu göndermemiş."
+            ))
+            .color(Color::Pending.into())
+            .build());
+    };
+
+    let (emoji, color) = match record.status {
+        VerificationStatus::Approved => ("✅", Color::Success),
+        VerificationStatus::Pending => ("⏳", Color::Pending),
+        VerificationStatus::Rejected => ("❌", Color::Failure),
+        VerificationStatus::Revoked => ("🚫", Color::Failure),
+    };
+
+    let mut embed = submission_embed(&ctx.form, &record.submission)
+        .title(format!("{emoji} {}", record.status.label()))
+        .field(EmbedFieldBuilder::new("Form ID", record.id.to_string()).inline())
+        .field(
+            EmbedFieldBuilder::new(
+                "Gönderilme Zamanı",
+                format!("<t:{}:f>", record.created_at.as_secs()),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                "Güncellenme Zamanı",
+                format!("<t:{}:f>", record.updated_at.as_secs()),
+            )
+            .inline(),
+        )
+        .color(color.into());
+
+    if let Some(reviewer_id) = record.reviewer_id {
+        let reviewed_at = record
+            .reviewed_at
+            .map(|reviewed_at| format!(", <t:{}:f>", reviewed_at.as_secs()))
+            .unwrap_or_default();
+        embed = embed.field(EmbedFieldBuilder::new(
+            "İnceleyen",
+            format!("<@{reviewer_id}>{reviewed_at}"),
+        ));
+    }
+
+    if let Some(revoker_id) = record.revoker_id {
+        let revoked_at = record
+            .revoked_at
+            .map(|revoked_at| format!(", <t:{}:f>", revoked_at.as_secs()))
+            .unwrap_or_default();
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Kaldıran",
+            format!("<@{revoker_id}>{revoked_at}"),
+        ));
+    }
+
+    if let Some(reason) = record
+        .status_reason
+        .as_ref()
+        .filter(|reason| !reason.is_empty())
+    {
+        embed = embed.field(EmbedFieldBuilder::new("Sebep", reason));
+    }
+
+    if let Some(message_id) = record.message_id {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Mesaj",
+            format!(
+                "https://discord.com/channels/{}/{}/{message_id}",
+                ctx.config.guild_id, ctx.config.verification_submissions_channel_id
+            ),
+        ));
+    }
+
+    if !submissions.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "Önceki Formlar",
+            earlier_submissions(&submissions)?,
+        ));
+    }
+
+    let audit_log_entries = ctx.storage.audit_log_entries(user_id)?;
+    if !audit_log_entries.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "İşlem Geçmişi",
+            history(&audit_log_entries, record.id)?,
+        ));
+    }
+
+    Ok(embed.build())
+}
+
+/// Lists the statuses of the earlier submissions, newest first.
+fn earlier_submissions(submissions: &[VerificationSubmissionRecord]) -> Result<String> {
+    let mut earlier_submissions = String::new();
+    for earlier_record in submissions
+        .iter()
+        .rev()
+        .take(MAX_LISTED_EARLIER_SUBMISSIONS)
+    {
+        writeln!(
+            earlier_submissions,
+            "- <t:{}:d>: {}",
+            earlier_record.created_at.as_secs(),
+            earlier_record.status.label()
+        )?;
+    }
+    let remaining = submissions
+        .len()
+        .saturating_sub(MAX_LISTED_EARLIER_SUBMISSIONS);
+    if remaining > 0 {
+        writeln!(earlier_submissions, "ve {remaining} form daha")?;
+    }
+
+    Ok(earlier_submissions)
+}
+
+/// Lists the moderation actions, noting the ones taken on a submission other
+/// than the latest one, as many as fit in an embed field.
+fn history(audit_log_entries: &[AuditLogEntry], latest_submission_id: u64) -> Result<String> {
+    // the longest the line noting the entries that aren't listed can be
+    let remaining_line_length = format!("ve {} kayıt daha\n", audit_log_entries.len())
+        .chars()
+        .count();
+
+    let mut history = String::new();
+    let mut history_length: usize = 0;
+    let mut listed: usize = 0;
+    for entry in audit_log_entries.iter().take(MAX_LISTED_AUDIT_LOG_ENTRIES) {
+        let line = history_line(entry, latest_submission_id)?;
+        let line_length = line.chars().count();
+        let reserved_length = if listed.saturating_add(1) == audit_log_entries.len() {
+            0
+        } else {
+            remaining_line_length
+        };
+        if history_length
+            .saturating_add(line_length)
+            .saturating_add(reserved_length)
+            > MAX_FIELD_LENGTH
+        {
+            break;
+        }
+
+        history.push_str(&line);
+        history_length = history_length.saturating_add(line_length);
+        listed = listed.saturating_add(1);
+    }
+    let remaining = audit_log_entries.len().saturating_sub(listed);
+    if remaining > 0 {
+        writeln!(history, "ve {remaining} kayıt daha")?;
+    }
+
+    Ok(history)
+}
+
+fn history_line(entry: &AuditLogEntry, latest_submission_id: u64) -> Result<String> {
+    let mut line = format!(
+        "- <t:{}:f> <@{}> {}",
+        entry.created_at.as_secs(),
+        entry.reviewer_id,
+        entry.action.label()
+    );
+    if entry.submission_id != latest_submission_id {
+        write!(line, " (form {})", entry.submission_id)?;
+    }
+    match entry.reason.as_ref().filter(|reason| !reason.is_empty()) {
+        Some(reason) => writeln!(line, ": {}", truncate(reason))?,
+        None => writeln!(line)?,
+    }
+
+    Ok(line)
+}
+
+/// Shortens the reason so that the history fits in an embed field.
+fn truncate(reason: &str) -> String {
+    if reason.chars().count() <= MAX_LISTED_REASON_LENGTH {
+        return reason.to_owned();
+    }
+
+    let mut truncated = reason
+        .chars()
+        .take(MAX_LISTED_REASON_LENGTH)
+        .collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{id::Id, util::Timestamp};
+
+    use super::{history, MAX_FIELD_LENGTH, MAX_LISTED_AUDIT_LOG_ENTRIES};
+    use crate::model::audit::{AuditAction, AuditLogEntry};
+
+    #[test]
+    fn fits_history_in_field() {
+        let entry = AuditLogEntry {
+            action: AuditAction::Revoke,
+            created_at: Timestamp::from_secs(4_102_444_800).unwrap(),
+            reason: Some("ş".repeat(1000)),
+            reviewer_id: Id::new(u64::MAX),
+            submission_id: u64::MAX,
+        };
+        let entries = vec![entry; MAX_LISTED_AUDIT_LOG_ENTRIES];
+
+        let history = history(&entries, 1).unwrap();
+
+        assert!(history.chars().count() <= MAX_FIELD_LENGTH);
+        let listed = history
+            .lines()
+            .filter(|line| line.starts_with("- "))
+            .count();
+        assert!(listed > 0);
+        assert_eq!(
+            history.lines().last(),
+            Some(format!("ve {} kayıt daha", entries.len() - listed).as_str())
+        );
+    }
+
+    #[test]
+    fn lists_whole_short_history() {
+        let entries = (1..=3)
+            .map(|submission_id| AuditLogEntry {
+                action: AuditAction::Reject,
+                created_at: Timestamp::from_secs(1_700_000_000).unwrap(),
+                reason: None,
+                reviewer_id: Id::new(10),
+                submission_id,
+            })
+            .collect::<Vec<_>>();
+
+        let history = history(&entries, 3).unwrap();
+
+        assert_eq!(history.lines().count(), 3);
+        assert!(history.ends_with("<@10> ❌ Reddetti\n"));
+    }
+}
@@ -1,103 +1,99 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use twilight_model::{
     application::interaction::Interaction,
-    channel::message::{
-        component::{ActionRow, TextInput, TextInputStyle},
-        Component,
-    },
+    channel::message::{component::ActionRow, Component},
     http::interaction::{InteractionResponse, InteractionResponseType},
+    id::{
+        marker::{RoleMarker, UserMarker},
+        Id,
+    },
 };
 use twilight_util::builder::InteractionResponseDataBuilder;
 
 use crate::interaction::{
-    verification_modal_submit::VerificationModalSubmit, InteractionContext, RunInteraction,
+    verification_modal_submit::{
+        submission_blocked_reason, submission_blocked_response, VerificationModalSubmit,
+    },
+    InteractionContext, RunInteraction,
 };
 
 pub struct ShowVerificationModal {
     ctx: InteractionContext,
+    member_roles: Vec<Id<RoleMarker>>,
+    user_id: Id<UserMarker>,
 }
 
 impl RunInteraction for ShowVerificationModal {
     const CUSTOM_ID: &'static str = "show-verification-modal";
 
-    #[allow(let_underscore_drop, clippy::let_underscore_untyped)]
-    async fn new(_: Interaction, ctx: InteractionContext) -> Result<Self> {
-        Ok(Self { ctx })
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let user_id = interaction
+            .author_id()
+            .ok_or_else(|| anyhow!("show verification modal interaction has no user"))?;
+        let member_roles = interaction
+            .member
+            .as_ref()
+            .map(|member| member.roles.clone())
+            .unwrap_or_default();
+
+        Ok(Self {
+            ctx,
+            member_roles,
+            user_id,
+        })
     }
 
     async fn run(self) -> Result<()> {
-        let name_surname_input = Component::TextInput(TextInput {
-            custom_id: "name-surname".to_owned(),
-            label: "İSİM SOYİSİM".to_owned(),
-            max_length: Some(32),
-            min_length: None,
-            placeholder: None,
-            required: None,
-            style: TextInputStyle::Short,
-            value: None,
-        });
-        let email_input = Component::TextInput(TextInput {
-            custom_id: "email".to_owned(),
-            label: "E-POSTA ADRESİ".to_owned(),
-            max_length: Some(254),
-            min_length: None,
-            placeholder: None,
-            required: None,
-            style: TextInputStyle::Short,
-            value: None,
-        });
-        let birthday_input = Component::TextInput(TextInput {
-            custom_id: "birthday".to_owned(),
-            label: "DOĞUM TARİHİ".to_owned(),
-            max_length: Some(10),
-            min_length: None,
-            placeholder: Some("GG.AA.YYYY".to_owned()),
-            required: None,
-            style: TextInputStyle::Short,
-            value: None,
-        });
-        let experience_input = Component::TextInput(TextInput {
-            custom_id: "experience".to_owned(),
-            label: "KAÇ YILDIR OYUN SEKTÖRÜNDESİNİZ?".to_owned(),
-            max_length: Some(2),
-            min_length: None,
-            placeholder: None,
-            required: None,
-            style: TextInputStyle::Short,
-            value: None,
-        });
-        let organization_input = Component::TextInput(TextInput {
-            custom_id: "organization".to_owned(),
-            label: "BULUNDUĞUNUZ KURUM VEYA EKİP".to_owned(),
-            max_length: Some(100),
-            min_length: None,
-            placeholder: None,
-            required: Some(false),
-            style: TextInputStyle::Short,
-            value: None,
-        });
+        if let Some(rate_limited) = self.ctx.core.rate_limiter.check(self.user_id)? {
+            return self
+                .ctx
+                .create_response(&submission_blocked_response(rate_limited.message()))
+                .await;
+        }
 
-        let rows = [
-            Component::ActionRow(ActionRow {
-                components: vec![name_surname_input],
-            }),
-            Component::ActionRow(ActionRow {
-                components: vec![email_input],
-            }),
-            Component::ActionRow(ActionRow {
-                components: vec![birthday_input],
-            }),
-            Component::ActionRow(ActionRow {
-                components: vec![experience_input],
-            }),
-            Component::ActionRow(ActionRow {
-                components: vec![organization_input],
-            }),
-        ];
+        let submissions = self.ctx.core.storage.submissions().await?;
+        if let Some(reason) = submission_blocked_reason(
+            &self.ctx.core,
+            &submissions,
+            self.user_id,
+            &self.member_roles,
+        ) {
+            return self
+                .ctx
+                .create_response(&submission_blocked_response(reason))
+                .await;
+        }
+
+        let form = &self.ctx.core.form;
+        let draft = self
+            .ctx
+            .core
+            .verification_drafts
+            .lock()
+            .map_err(|err| anyhow!("verification drafts mutex is poisoned: {err}"))?
+            .get(&self.user_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let rows = form
+            .fields
+            .iter()
+            .map(|field| {
+                let value = draft
+                    .iter()
+                    .find(|answer| answer.custom_id == field.custom_id)
+                    .map(|answer| answer.value.clone())
+                    .filter(|value| !value.is_empty());
+
+                Component::ActionRow(ActionRow {
+                    components: vec![Component::TextInput(field.text_input(value))],
+                })
+            })
+            .collect::<Vec<_>>();
 
         let response = InteractionResponseDataBuilder::new()
             .custom_id(VerificationModalSubmit::CUSTOM_ID)
-            .title("📝 Doğrulanma Formu")
+            .title(&form.title)
             .components(rows)
             .build();
 
@@ -0,0 +1,256 @@
+use std::slice;
+
+use anyhow::{anyhow, Result};
+use tracing::warn;
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::{application_command::CommandOptionValue, Interaction},
+    },
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::{
+    command::{BooleanBuilder, CommandBuilder, StringBuilder, UserBuilder},
+    embed::{EmbedBuilder, EmbedFieldBuilder},
+};
+
+use crate::{
+    color::Color,
+    discord_error::{is_api_error, UNKNOWN_MEMBER_ERROR_CODE, UNKNOWN_ROLE_ERROR_CODE},
+    interaction::{
+        command_data,
+        verification_modal_submit::{submission_embed, with_reviewer},
+        CreateCommand, InteractionContext, RunInteraction,
+    },
+    model::{
+        audit::AuditAction,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+};
+
+const USER_OPTION: &str = "kullanıcı";
+const REASON_OPTION: &str = "sebep";
+const RESET_NICKNAME_OPTION: &str = "ismi_sıfırla";
+
+/// Removes the verified role from a member and marks their approved
+/// submission as revoked.
+pub struct UnverifyMember {
+    ctx: InteractionContext,
+    guild_id: Id<GuildMarker>,
+    moderator_id: Id<UserMarker>,
+    reason: String,
+    reset_nickname: bool,
+    user_id: Id<UserMarker>,
+}
+
+impl UnverifyMember {
+    /// Marks the user's latest approved submission as revoked, returning it
+    /// if there's one.
+    async fn revoke_submission(&self) -> Result<Option<VerificationSubmissionRecord>> {
+        let Some(submission_id) = self
+            .ctx
+            .core
+            .storage
+            .submissions()
+            .await?
+            .into_iter()
+            .filter(|record| {
+                record.submission.user_id == self.user_id
+                    && record.status == VerificationStatus::Approved
+            })
+            .max_by_key(|record| record.id)
+            .map(|record| record.id)
+        else {
+            return Ok(None);
+        };
+
+        // held until the submission is updated so that it's not reviewed
+        // meanwhile
+        let _lock = self.ctx.core.submission_locks.lock(submission_id).await?;
+        let mut record = self.ctx.core.storage.submission(submission_id).await?;
+        if record.status != VerificationStatus::Approved {
+            return Ok(None);
+        }
+
+        record.revoke(self.reason.clone(), self.moderator_id)?;
+        self.ctx.core.storage.update(&record).await?;
+        self.ctx
+            .core
+            .storage
+            .add_audit_log_entry(&record, AuditAction::Revoke)?;
+
+        Ok(Some(record))
+    }
+}
+
+impl CreateCommand for UnverifyMember {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "Bir kullanıcının doğrulamasını kaldır",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(UserBuilder::new(USER_OPTION, "Doğrulaması kaldırılacak kullanıcı").required(true))
+        .option(
+            StringBuilder::new(REASON_OPTION, "Doğrulamanın kaldırılma sebebi")
+                .max_length(1000)
+                .required(true),
+        )
+        .option(BooleanBuilder::new(
+            RESET_NICKNAME_OPTION,
+            "Kullanıcının sunucudaki ismi de sıfırlansın mı",
+        ))
+        .validate()?
+        .build())
+    }
+}
+
+impl RunInteraction for UnverifyMember {
+    const CUSTOM_ID: &'static str = "doğrulamayı_kaldır";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let guild_id = interaction
+            .guild_id
+            .ok_or_else(|| anyhow!("unverify member interaction doesnt have a guild id"))?;
+
+        let moderator = interaction
+            .author()
+            .ok_or_else(|| anyhow!("unverify member interaction has no user"))?;
+        let moderator_id = moderator.id;
+
+        let mut user_id = None;
+        let mut reason = None;
+        let mut reset_nickname = false;
+        for option in command_data(interaction)?.options {
+            match (option.name.as_str(), option.value) {
+                (USER_OPTION, CommandOptionValue::User(value)) => user_id = Some(value),
+                (REASON_OPTION, CommandOptionValue::String(value)) => reason = Some(value),
+                (RESET_NICKNAME_OPTION, CommandOptionValue::Boolean(value)) => {
+                    reset_nickname = value;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            ctx,
+            guild_id,
+            moderator_id,
+            reason: reason.ok_or_else(|| anyhow!("unverify member command has no reason"))?,
+            reset_nickname,
+            user_id: user_id.ok_or_else(|| anyhow!("unverify member command has no user"))?,
+        })
+    }
+
+    async fn run(self) -> Result<()> {
+        // the stores and the channel can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        // the submission is still revoked if the member left or the role was
+        // deleted
+        let role_removed = match self
+            .ctx
+            .core
+            .client
+            .remove_guild_member_role(
+                self.guild_id,
+                self.user_id,
+                self.ctx.core.config.verified_role_id,
+            )
+            .await
+        {
+            Ok(_) => true,
+            Err(err)
+                if is_api_error(&err, UNKNOWN_MEMBER_ERROR_CODE)
+                    || is_api_error(&err, UNKNOWN_ROLE_ERROR_CODE) =>
+            {
+                warn!(?err, "couldn't remove verified role of unverified member");
+                false
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let nickname_reset = if self.reset_nickname {
+            match self
+                .ctx
+                .core
+                .client
+                .update_guild_member(self.guild_id, self.user_id)
+                .nick(None)?
+                .await
+            {
+                Ok(_) => Some(true),
+                Err(err) => {
+                    warn!(?err, "couldn't reset nickname of unverified member");
+                    Some(false)
+                }
+            }
+        } else {
+            None
+        };
+
+        let record = self.revoke_submission().await?;
+
+        let mut builder = match record.as_ref() {
+            Some(revoked) => with_reviewer(
+                submission_embed(&self.ctx.core.form, &revoked.submission),
+                revoked,
+                None,
+            )
+            .field(EmbedFieldBuilder::new(
+                "Kaldıran",
+                format!("<@{}>", self.moderator_id),
+            )),
+            None => EmbedBuilder::new()
+                .field(EmbedFieldBuilder::new(
+                    "Kullanıcı",
+                    format!("<@{}>", self.user_id),
+                ))
+                .field(EmbedFieldBuilder::new(
+                    "Kaldıran",
+                    format!("<@{}>", self.moderator_id),
+                )),
+        }
+        .title("🚫 Kullanıcının doğrulaması kaldırıldı")
+        .field(EmbedFieldBuilder::new("Kaldırılma Sebebi", &self.reason))
+        .color(Color::Failure.into());
+        if !role_removed {
+            builder = builder.field(EmbedFieldBuilder::new(
+                "Rol",
+                "❌ Kullanıcı sunucuda olmadığı ya da rol silindiği için alınamadı",
+            ));
+        }
+        if let Some(reset) = nickname_reset {
+            builder = builder.field(EmbedFieldBuilder::new(
+                "İsim",
+                if reset {
+                    "✅ Sıfırlandı"
+                } else {
+                    "❌ Sıfırlanamadı"
+                },
+            ));
+        }
+        if record.is_none() {
+            builder = builder.field(EmbedFieldBuilder::new(
+                "⚠️ Form bulunamadı",
+                "Kullanıcının onaylanmış bir formu olmadığı için sadece rolü alındı.",
+            ));
+        }
+        let embed = builder.build();
+
+        self.ctx
+            .core
+            .client
+            .create_message(self.ctx.core.config.verification_submissions_channel_id)
+            .embeds(slice::from_ref(&embed))?
+            .await?;
+
+        self.ctx.followup(&[embed], true).await
+    }
+}
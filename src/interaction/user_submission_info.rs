@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::Interaction,
+    },
+    guild::Permissions,
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::command::CommandBuilder;
+
+use crate::interaction::{
+    command_data, show_submission_info::submission_info_embed, CreateCommand, InteractionContext,
+    RunInteraction,
+};
+
+/// The user context menu version of `/doğrulama_bilgisi`.
+pub struct UserSubmissionInfo {
+    ctx: InteractionContext,
+    user_id: Id<UserMarker>,
+}
+
+impl CreateCommand for UserSubmissionInfo {
+    fn command() -> Result<Command> {
+        Ok(CommandBuilder::new(Self::CUSTOM_ID, "", CommandType::User)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .validate()?
+            .build())
+    }
+}
+
+impl RunInteraction for UserSubmissionInfo {
+    const CUSTOM_ID: &'static str = "Doğrulama Bilgisi";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let user_id = command_data(interaction)?
+            .target_id
+            .ok_or_else(|| anyhow!("submission info user command has no target"))?
+            .cast();
+
+        Ok(Self { ctx, user_id })
+    }
+
+    async fn run(self) -> Result<()> {
+        // reading the submissions can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        let embed = submission_info_embed(&self.ctx.core, self.user_id).await?;
+        self.ctx.followup(&[embed], true).await
+    }
+}
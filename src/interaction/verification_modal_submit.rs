@@ -1,77 +1,87 @@
-use std::vec::IntoIter;
+use std::collections::HashMap;
 
 use anyhow::{anyhow, bail, Result};
-use tracing::warn;
 use twilight_model::{
-    application::interaction::{
-        modal::ModalInteractionDataActionRow, Interaction, InteractionData,
-    },
+    application::interaction::{Interaction, InteractionData},
     channel::message::{
         component::{ActionRow, Button, ButtonStyle},
+        embed::Embed,
         Component, MessageFlags, ReactionType,
     },
     http::interaction::{InteractionResponse, InteractionResponseType},
+    id::{
+        marker::{MessageMarker, RoleMarker, UserMarker},
+        Id,
+    },
 };
 use twilight_util::builder::{
-    embed::{EmbedBuilder, EmbedFieldBuilder},
+    embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder},
     InteractionResponseDataBuilder,
 };
 
 use crate::{
     color::Color,
-    interaction::{approve_verification::ApproveVerification, InteractionContext, RunInteraction},
-    model::verification::VerificationSubmission,
+    interaction::{
+        approve_verification::ApproveVerification, custom_id_with_arg,
+        reject_verification::reject_button, show_verification_modal::ShowVerificationModal,
+        InteractionContext, RunInteraction,
+    },
+    model::{
+        form::{FormField, VerificationForm, NAME_SURNAME_ID},
+        verification::{
+            FormAnswer, VerificationStatus, VerificationSubmission, VerificationSubmissionRecord,
+        },
+    },
+    name, Context,
 };
 
-#[derive(Clone)]
 pub struct VerificationModalSubmit {
     ctx: InteractionContext,
-    submission: VerificationSubmission,
+    member_roles: Vec<Id<RoleMarker>>,
+    /// Whether characters were removed from the name the user entered.
+    name_altered: bool,
+    problems: Vec<String>,
+    raw_answers: Vec<FormAnswer>,
+    record: VerificationSubmissionRecord,
 }
 
 impl VerificationModalSubmit {
-    fn next_component_value(
-        components: &mut IntoIter<ModalInteractionDataActionRow>,
-    ) -> Result<String> {
-        components
-            .next()
-            .and_then(|row| row.components.into_iter().next())
-            .and_then(|component| component.value)
-            .ok_or_else(|| anyhow!("modal components iterator is drained"))
-    }
-
-    async fn create_verification_submission_message(self) -> Result<()> {
-        let embed = EmbedBuilder::new()
+    async fn create_verification_submission_message(
+        &self,
+        email_reused_by: &[Id<UserMarker>],
+    ) -> Result<Id<MessageMarker>> {
+        let mut embed = submission_embed(&self.ctx.core.form, &self.record.submission)
             .title("❔ Doğrulanma formu dolduruldu")
-            .field(EmbedFieldBuilder::new(
-                "Kullanıcı",
-                format!("<@{}>", self.submission.user_id),
-            ))
-            .field(EmbedFieldBuilder::new(
-                "İsim Soyisim",
-                self.submission.name_surname,
-            ))
-            .field(EmbedFieldBuilder::new(
-                "E-Posta Adresi",
-                self.submission.email,
-            ))
-            .field(EmbedFieldBuilder::new(
-                "Doğum Tarihi",
-                self.submission.birthday,
-            ))
-            .field(EmbedFieldBuilder::new(
-                "Yıllık Oyun Sektörü Tecrübesi",
-                self.submission.experience,
-            ))
-            .field(EmbedFieldBuilder::new(
-                "Kurum veya Ekip",
-                self.submission.organization,
-            ))
-            .color(Color::Pending.into())
-            .build();
+            .color(Color::Pending.into());
+
+        if self.name_altered {
+            let raw_name = self
+                .raw_answers
+                .iter()
+                .find(|answer| answer.custom_id == NAME_SURNAME_ID)
+                .map_or("", |answer| answer.value.as_str());
+            embed = embed.field(EmbedFieldBuilder::new(
+                "⚠️ İsimden bazı karakterler kaldırıldı",
+                format!("Girilen isim: `{}`", raw_name.escape_debug()),
+            ));
+        }
+
+        if !email_reused_by.is_empty() {
+            embed = embed.field(EmbedFieldBuilder::new(
+                "⚠️ Bu e-posta başka hesaplarca da kullanılmış",
+                email_reused_by
+                    .iter()
+                    .map(|user_id| format!("<@{user_id}>"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
+        }
 
         let approve_button = Component::Button(Button {
-            custom_id: Some(ApproveVerification::CUSTOM_ID.to_owned()),
+            custom_id: Some(custom_id_with_arg(
+                ApproveVerification::CUSTOM_ID,
+                self.record.id,
+            )),
             disabled: false,
             emoji: Some(ReactionType::Unicode {
                 name: "✅".to_owned(),
@@ -81,27 +91,51 @@ impl VerificationModalSubmit {
             url: None,
         });
 
-        self.ctx
+        let message = self
+            .ctx
             .core
             .client
             .create_message(self.ctx.core.config.verification_submissions_channel_id)
-            .embeds(&[embed])?
+            .embeds(&[embed.build()])?
             .components(&[Component::ActionRow(ActionRow {
-                components: vec![approve_button],
+                components: vec![approve_button, reject_button(self.record.id)],
             })])?
+            .await?
+            .model()
             .await?;
 
-        Ok(())
+        Ok(message.id)
     }
 
-    async fn append_to_sheet(self) -> Result<()> {
-        self.ctx
-            .core
-            .sheets
-            .append_verification_submission(self.submission)
-            .await?;
+    /// Returns the other accounts whose submissions have the same email.
+    fn email_reused_by(&self, submissions: &[VerificationSubmissionRecord]) -> Vec<Id<UserMarker>> {
+        let Some(email_field) = self.ctx.core.form.email_field() else {
+            return vec![];
+        };
+        let Some(email) = self
+            .record
+            .submission
+            .answer(&email_field.custom_id)
+            .filter(|email| !email.is_empty())
+        else {
+            return vec![];
+        };
 
-        Ok(())
+        let mut user_ids = vec![];
+        for record in submissions {
+            let user_id = record.submission.user_id;
+            if user_id != self.record.submission.user_id
+                && !user_ids.contains(&user_id)
+                && record
+                    .submission
+                    .answer(&email_field.custom_id)
+                    .is_some_and(|other_email| other_email.eq_ignore_ascii_case(email))
+            {
+                user_ids.push(user_id);
+            }
+        }
+
+        user_ids
     }
 
     async fn respond(self) -> Result<()> {
@@ -113,12 +147,47 @@ impl VerificationModalSubmit {
             .color(Color::Success.into())
             .build();
 
+        self.ctx.followup(&[response_embed], true).await
+    }
+
+    async fn respond_invalid(self) -> Result<()> {
+        self.ctx
+            .core
+            .verification_drafts
+            .lock()
+            .map_err(|err| anyhow!("verification drafts mutex is poisoned: {err}"))?
+            .insert(self.record.submission.user_id, self.raw_answers);
+
+        let response_embed = EmbedBuilder::new()
+            .title("⚠️ Formunuzda hatalar var")
+            .description(format!(
+                "{}\n\nAşağıdaki butonla formu cevaplarınız doldurulmuş olarak tekrar açıp \
+                 düzeltebilirsiniz.",
+                self.problems.join("\n")
+            ))
+            .color(Color::Failure.into())
+            .build();
+
+        let edit_button = Component::Button(Button {
+            custom_id: Some(ShowVerificationModal::CUSTOM_ID.to_owned()),
+            disabled: false,
+            emoji: Some(ReactionType::Unicode {
+                name: "📝".to_owned(),
+            }),
+            label: Some("Formu Düzelt".to_owned()),
+            style: ButtonStyle::Primary,
+            url: None,
+        });
+
         self.ctx
             .create_response(&InteractionResponse {
                 kind: InteractionResponseType::ChannelMessageWithSource,
                 data: Some(
                     InteractionResponseDataBuilder::new()
                         .embeds([response_embed])
+                        .components([Component::ActionRow(ActionRow {
+                            components: vec![edit_button],
+                        })])
                         .flags(MessageFlags::EPHEMERAL)
                         .build(),
                 ),
@@ -136,6 +205,11 @@ impl RunInteraction for VerificationModalSubmit {
         let user_id = interaction
             .author_id()
             .ok_or_else(|| anyhow!("verification modal interaction has no user"))?;
+        let member_roles = interaction
+            .member
+            .as_ref()
+            .map(|member| member.roles.clone())
+            .unwrap_or_default();
 
         let InteractionData::ModalSubmit(modal) = interaction
             .data
@@ -144,70 +218,249 @@ impl RunInteraction for VerificationModalSubmit {
             bail!("verification modal data is not of kind modal submit")
         };
 
-        let mut components = modal.components.into_iter();
-        let name_surname = to_title_case(&Self::next_component_value(&mut components)?);
-        let email = Self::next_component_value(&mut components)?;
-        let birthday = Self::next_component_value(&mut components)?;
-        let experience = Self::next_component_value(&mut components)?;
-        let organization = Self::next_component_value(&mut components)?;
+        let mut values = modal
+            .components
+            .into_iter()
+            .flat_map(|row| row.components)
+            .filter_map(|component| Some((component.custom_id, component.value?)))
+            .collect::<HashMap<_, _>>();
+
+        let mut name_altered = false;
+        let mut problems = vec![];
+        let mut raw_answers = vec![];
+        let mut answers = vec![];
+        for field in &ctx.core.form.fields {
+            let raw_value = values.remove(&field.custom_id).unwrap_or_default();
+            if let Some(problem) = field.check(&raw_value)? {
+                problems.push(problem);
+            }
+
+            let (value, removed_chars) = normalize_answer(&ctx.core, field, &raw_value);
+            name_altered |= removed_chars;
+
+            answers.push(FormAnswer {
+                custom_id: field.custom_id.clone(),
+                value,
+            });
+            raw_answers.push(FormAnswer {
+                custom_id: field.custom_id.clone(),
+                value: raw_value,
+            });
+        }
+
+        let submission = VerificationSubmission { answers, user_id };
 
         Ok(Self {
-            submission: VerificationSubmission {
-                birthday,
-                email,
-                experience,
-                name_surname,
-                organization: if organization.is_empty() {
-                    "Yok".to_owned()
-                } else {
-                    organization
-                },
-                user_id,
-            },
             ctx,
+            member_roles,
+            name_altered,
+            problems,
+            raw_answers,
+            record: VerificationSubmissionRecord::new(interaction.id.get(), submission)?,
         })
     }
 
-    async fn run(self) -> Result<()> {
-        if let Err(err) = self.clone().append_to_sheet().await {
-            warn!(
-                ?err,
-                "couldn't append verification submission to sheet: {:#?}", self.submission
-            );
+    async fn run(mut self) -> Result<()> {
+        if !self.problems.is_empty() {
+            return self.respond_invalid().await;
         }
 
-        self.clone()
-            .create_verification_submission_message()
+        self.ctx
+            .core
+            .verification_drafts
+            .lock()
+            .map_err(|err| anyhow!("verification drafts mutex is poisoned: {err}"))?
+            .remove(&self.record.submission.user_id);
+
+        // the stores and the channel can take longer than the interaction
+        // deadline
+        self.ctx.defer(true).await?;
+
+        // held until the submission is saved so that concurrent submits of
+        // the user don't all pass the checks
+        let _lock = self
+            .ctx
+            .core
+            .user_locks
+            .lock(self.record.submission.user_id)
             .await?;
 
+        let submissions = self.ctx.core.storage.submissions().await?;
+        if let Some(reason) = submission_blocked_reason(
+            &self.ctx.core,
+            &submissions,
+            self.record.submission.user_id,
+            &self.member_roles,
+        ) {
+            return self
+                .ctx
+                .followup(&[submission_blocked_embed(reason)], true)
+                .await;
+        }
+
+        if let Some(rate_limited) = self
+            .ctx
+            .core
+            .rate_limiter
+            .check_and_record(self.record.submission.user_id)?
+        {
+            return self
+                .ctx
+                .followup(&[submission_blocked_embed(rate_limited.message())], true)
+                .await;
+        }
+
+        let email_reused_by = self.email_reused_by(&submissions);
+        let message_id = self
+            .ctx
+            .core
+            .storage
+            .append_and_post(
+                &self.record,
+                self.create_verification_submission_message(&email_reused_by),
+            )
+            .await?;
+        self.record.message_id = Some(message_id);
+        self.ctx.core.storage.update(&self.record).await?;
+
         self.respond().await?;
 
         Ok(())
     }
 }
 
-fn to_title_case(string: &str) -> String {
-    let mut title = String::new();
+/// Returns the value that's saved for the answer to the field and whether
+/// characters were removed from it.
+pub fn normalize_answer(ctx: &Context, field: &FormField, raw_value: &str) -> (String, bool) {
+    if raw_value.trim().is_empty() {
+        (field.empty_value.clone().unwrap_or_default(), false)
+    } else if field.custom_id == NAME_SURNAME_ID {
+        let normalized_name = name::normalize(raw_value, &ctx.config.name_lowercase_particles);
+        (normalized_name.name, normalized_name.removed_chars)
+    } else {
+        (raw_value.trim().to_owned(), false)
+    }
+}
+
+/// Returns why the user can't submit the form, if they already are verified
+/// or have a submission that's pending or approved.
+pub fn submission_blocked_reason(
+    ctx: &Context,
+    submissions: &[VerificationSubmissionRecord],
+    user_id: Id<UserMarker>,
+    member_roles: &[Id<RoleMarker>],
+) -> Option<String> {
+    if member_roles.contains(&ctx.config.verified_role_id) {
+        return Some("Zaten doğrulanmışsınız, formu tekrar doldurmanıza gerek yok.".to_owned());
+    }
 
-    for word in string.split_whitespace() {
-        let mut chars = word.chars();
-        let Some(first_char) = chars.next() else {
-            continue;
-        };
+    let record = blocking_submission(submissions, user_id)?;
 
-        let first_char_uppercase = if first_char == 'i' {
-            'İ'.to_string()
+    Some(match record.status {
+        VerificationStatus::Approved => "Formunuz zaten onaylanmış, bir sorun olduğunu \
+                                         düşünüyorsanız yetkililere ulaşın."
+            .to_owned(),
+        _ => format!(
+            "<t:{}:R> gönderdiğiniz formunuz henüz inceleniyor, lütfen sonucunu bekleyin.",
+            record.created_at.as_secs()
+        ),
+    })
+}
+
+/// Returns the latest submission of the user that's pending or approved.
+pub fn blocking_submission(
+    submissions: &[VerificationSubmissionRecord],
+    user_id: Id<UserMarker>,
+) -> Option<&VerificationSubmissionRecord> {
+    submissions
+        .iter()
+        .filter(|record| {
+            record.submission.user_id == user_id
+                && !matches!(
+                    record.status,
+                    VerificationStatus::Rejected | VerificationStatus::Revoked
+                )
+        })
+        .max_by_key(|record| record.id)
+}
+
+pub fn submission_blocked_embed(reason: String) -> Embed {
+    EmbedBuilder::new()
+        .title("⛔ Formu şu anda gönderemezsiniz")
+        .description(reason)
+        .color(Color::Failure.into())
+        .build()
+}
+
+pub fn submission_blocked_response(reason: String) -> InteractionResponse {
+    InteractionResponse {
+        kind: InteractionResponseType::ChannelMessageWithSource,
+        data: Some(
+            InteractionResponseDataBuilder::new()
+                .embeds([submission_blocked_embed(reason)])
+                .flags(MessageFlags::EPHEMERAL)
+                .build(),
+        ),
+    }
+}
+
+pub fn submission_embed(
+    form: &VerificationForm,
+    submission: &VerificationSubmission,
+) -> EmbedBuilder {
+    let mut embed = EmbedBuilder::new().field(EmbedFieldBuilder::new(
+        "Kullanıcı",
+        format!("<@{}>", submission.user_id),
+    ));
+
+    for answer in &submission.answers {
+        let title = form
+            .field(&answer.custom_id)
+            .map_or(answer.custom_id.as_str(), |field| {
+                field.embed_title.as_str()
+            });
+        let value = if answer.value.is_empty() {
+            "-"
         } else {
-            first_char.to_uppercase().to_string()
+            answer.value.as_str()
         };
-        title.push_str(&first_char_uppercase);
 
-        for char in chars {
-            title.push_str(&char.to_lowercase().to_string());
-        }
+        embed = embed.field(EmbedFieldBuilder::new(title, value));
+    }
 
-        title.push(' ');
+    let nickname = name::nickname(submission.name_surname());
+    if nickname != submission.name_surname() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            "⚠️ İsim kısaltıldı",
+            format!(
+                "İsim {} karakteri geçtiği için Discord'da `{nickname}` olarak ayarlanır.",
+                name::MAX_NICKNAME_LENGTH
+            ),
+        ));
     }
 
-    title
+    embed
+}
+
+/// Adds who reviewed the submission and when to the embed's footer, the
+/// reviewer's name is left out if it's not known.
+pub fn with_reviewer(
+    embed: EmbedBuilder,
+    record: &VerificationSubmissionRecord,
+    reviewer_name: Option<&str>,
+) -> EmbedBuilder {
+    let Some(reviewer_id) = record.reviewer_id else {
+        return embed;
+    };
+
+    let footer = reviewer_name.map_or_else(
+        || format!("İnceleyen: {reviewer_id}"),
+        |name| format!("İnceleyen: {name} ({reviewer_id})"),
+    );
+    let with_footer = embed.footer(EmbedFooterBuilder::new(footer));
+
+    match record.reviewed_at {
+        Some(reviewed_at) => with_footer.timestamp(reviewed_at),
+        None => with_footer,
+    }
 }
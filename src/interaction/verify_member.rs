@@ -0,0 +1,271 @@
+use std::{collections::HashMap, slice};
+
+use anyhow::{anyhow, Result};
+use twilight_model::{
+    application::{
+        command::{Command, CommandType},
+        interaction::{application_command::CommandOptionValue, Interaction},
+    },
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::{
+    command::{CommandBuilder, StringBuilder, UserBuilder},
+    embed::{EmbedBuilder, EmbedFieldBuilder},
+};
+
+use crate::{
+    color::Color,
+    interaction::{
+        approve_verification::{Approval, ApprovalStep},
+        command_data,
+        verification_modal_submit::{blocking_submission, normalize_answer, submission_embed},
+        InteractionContext, RunInteraction,
+    },
+    model::{
+        form::VerificationForm,
+        verification::{
+            FormAnswer, VerificationStatus, VerificationSubmission, VerificationSubmissionRecord,
+        },
+    },
+};
+
+const USER_OPTION: &str = "kullanıcı";
+
+/// Verifies a member with the form filled in by a moderator, such as for the
+/// members verified in person.
+pub struct VerifyMember {
+    ctx: InteractionContext,
+    guild_id: Id<GuildMarker>,
+    id: u64,
+    /// The roles of the user, empty if they're not in the guild.
+    member_roles: Vec<Id<RoleMarker>>,
+    moderator_id: Id<UserMarker>,
+    moderator_name: String,
+    user_id: Id<UserMarker>,
+    values: HashMap<String, String>,
+}
+
+impl VerifyMember {
+    /// Creates the command with an option for each field of the form, which
+    /// is why this isn't [`crate::interaction::CreateCommand`].
+    pub fn command(form: &VerificationForm) -> Result<Command> {
+        let mut command = CommandBuilder::new(
+            Self::CUSTOM_ID,
+            "Bir kullanıcıyı formu sizin doldurduğunuz bilgilerle doğrula",
+            CommandType::ChatInput,
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(UserBuilder::new(USER_OPTION, "Doğrulanacak kullanıcı").required(true));
+
+        // the required options have to come before the optional ones
+        let mut fields = form.fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|field| !field.required);
+        for field in fields {
+            let mut option =
+                StringBuilder::new(&field.custom_id, &field.embed_title).required(field.required);
+            if let Some(max_length) = field.max_length {
+                option = option.max_length(max_length);
+            }
+            if let Some(min_length) = field.min_length {
+                option = option.min_length(min_length);
+            }
+
+            command = command.option(option);
+        }
+
+        Ok(command.validate()?.build())
+    }
+}
+
+impl VerifyMember {
+    /// Returns why the user can't be verified, if they already are verified
+    /// or have a submission that's pending or approved.
+    async fn blocked_reason(&self) -> Result<Option<String>> {
+        if self
+            .member_roles
+            .contains(&self.ctx.core.config.verified_role_id)
+        {
+            return Ok(Some("Kullanıcı zaten doğrulanmış.".to_owned()));
+        }
+
+        let submissions = self.ctx.core.storage.submissions().await?;
+        let Some(record) = blocking_submission(&submissions, self.user_id) else {
+            return Ok(None);
+        };
+
+        let message_link = record
+            .message_id
+            .map(|message_id| {
+                format!(
+                    " [Forma git](https://discord.com/channels/{}/{}/{message_id})",
+                    self.guild_id, self.ctx.core.config.verification_submissions_channel_id
+                )
+            })
+            .unwrap_or_default();
+
+        Ok(Some(match record.status {
+            VerificationStatus::Approved => {
+                format!("Kullanıcının formu zaten onaylanmış.{message_link}")
+            }
+            _ => format!(
+                "Kullanıcının <t:{}:R> gönderdiği formu henüz incelenmedi.{message_link}",
+                record.created_at.as_secs()
+            ),
+        }))
+    }
+}
+
+impl RunInteraction for VerifyMember {
+    const CUSTOM_ID: &'static str = "elle_doğrula";
+
+    async fn new(interaction: Interaction, ctx: InteractionContext) -> Result<Self> {
+        let guild_id = interaction
+            .guild_id
+            .ok_or_else(|| anyhow!("verify member interaction doesnt have a guild id"))?;
+        let id = interaction.id.get();
+
+        let moderator = interaction
+            .author()
+            .ok_or_else(|| anyhow!("verify member interaction has no user"))?;
+        let moderator_id = moderator.id;
+        let moderator_name = moderator.name.clone();
+
+        let data = command_data(interaction)?;
+        let mut user_option = None;
+        let mut values = HashMap::new();
+        for option in data.options {
+            match option.value {
+                CommandOptionValue::User(value) if option.name == USER_OPTION => {
+                    user_option = Some(value);
+                }
+                CommandOptionValue::String(value) => {
+                    values.insert(option.name, value);
+                }
+                _ => {}
+            }
+        }
+
+        let user_id = user_option.ok_or_else(|| anyhow!("verify member command has no user"))?;
+        let member_roles = data
+            .resolved
+            .and_then(|mut resolved| resolved.members.remove(&user_id))
+            .map(|member| member.roles)
+            .unwrap_or_default();
+
+        Ok(Self {
+            ctx,
+            guild_id,
+            id,
+            member_roles,
+            moderator_id,
+            moderator_name,
+            user_id,
+            values,
+        })
+    }
+
+    async fn run(mut self) -> Result<()> {
+        // the approval steps can take longer than the interaction deadline
+        self.ctx.defer(true).await?;
+
+        let mut problems = vec![];
+        let mut answers = vec![];
+        for field in &self.ctx.core.form.fields {
+            let raw_value = self.values.remove(&field.custom_id).unwrap_or_default();
+            if let Some(problem) = field.check(&raw_value)? {
+                problems.push(problem);
+            }
+
+            answers.push(FormAnswer {
+                custom_id: field.custom_id.clone(),
+                value: normalize_answer(&self.ctx.core, field, &raw_value).0,
+            });
+        }
+
+        if !problems.is_empty() {
+            let embed = EmbedBuilder::new()
+                .title("⚠️ Formda hatalar var")
+                .description(problems.join("\n"))
+                .color(Color::Failure.into())
+                .build();
+
+            return self.ctx.followup(&[embed], true).await;
+        }
+
+        // held until the submission is saved so that the user can't submit
+        // the form meanwhile
+        let _lock = self.ctx.core.user_locks.lock(self.user_id).await?;
+
+        if let Some(reason) = self.blocked_reason().await? {
+            let embed = EmbedBuilder::new()
+                .title("⛔ Kullanıcı elle doğrulanamaz")
+                .description(reason)
+                .color(Color::Failure.into())
+                .build();
+
+            return self.ctx.followup(&[embed], true).await;
+        }
+
+        let mut record = VerificationSubmissionRecord::new(
+            self.id,
+            VerificationSubmission {
+                answers,
+                user_id: self.user_id,
+            },
+        )?;
+
+        let channel_id = self.ctx.core.config.verification_submissions_channel_id;
+        let pending_embed = submission_embed(&self.ctx.core.form, &record.submission)
+            .title("⏳ Kullanıcı elle doğrulanıyor")
+            .color(Color::Pending.into())
+            .build();
+        let message_id = self
+            .ctx
+            .core
+            .storage
+            .append_and_post(&record, async {
+                Ok(self
+                    .ctx
+                    .core
+                    .client
+                    .create_message(channel_id)
+                    .embeds(&[pending_embed])?
+                    .await?
+                    .model()
+                    .await?
+                    .id)
+            })
+            .await?;
+        record.message_id = Some(message_id);
+        self.ctx.core.storage.update(&record).await?;
+
+        let (approval_embed, components) = Approval {
+            ctx: &self.ctx.core,
+            guild_id: self.guild_id,
+            reviewer_id: self.moderator_id,
+            reviewer_name: &self.moderator_name,
+        }
+        .run(&ApprovalStep::ALL, &mut record)
+        .await;
+        let embed = approval_embed
+            .field(EmbedFieldBuilder::new(
+                "ℹ️ Elle doğrulandı",
+                format!("Form <@{}> tarafından dolduruldu.", self.moderator_id),
+            ))
+            .build();
+
+        self.ctx
+            .core
+            .client
+            .update_message(channel_id, message_id)
+            .embeds(Some(slice::from_ref(&embed)))?
+            .components(Some(&components))?
+            .await?;
+
+        self.ctx.followup(&[embed], true).await
+    }
+}
@@ -0,0 +1,46 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Result};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use twilight_model::id::{marker::UserMarker, Id};
+
+/// Makes sure a submission is reviewed by only one interaction at a time, so
+/// that concurrent clicks don't approve or reject it twice.
+pub type SubmissionLocks = Locks<u64>;
+
+/// Makes sure a user's submission is handled by only one interaction at a
+/// time, so that concurrent submits aren't all accepted.
+pub type UserLocks = Locks<Id<UserMarker>>;
+
+#[derive(Debug)]
+pub struct Locks<K>(Mutex<HashMap<K, Arc<AsyncMutex<()>>>>);
+
+impl<K> Default for Locks<K> {
+    fn default() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+}
+
+impl<K: Eq + Hash> Locks<K> {
+    /// Waits until no other interaction holds the lock of the key, the key is
+    /// locked until the returned guard is dropped.
+    pub async fn lock(&self, key: K) -> Result<OwnedMutexGuard<()>> {
+        let lock = {
+            let mut locks = self
+                .0
+                .lock()
+                .map_err(|err| anyhow!("locks mutex is poisoned: {err}"))?;
+
+            // the locks that aren't held by anyone are only referenced by the map
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+            Arc::clone(locks.entry(key).or_default())
+        };
+
+        Ok(lock.lock_owned().await)
+    }
+}
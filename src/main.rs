@@ -1,21 +1,31 @@
 // TODO: change .ok_or_else to anyhow's .context
 
 mod color;
+mod database;
+mod discord_error;
 mod interaction;
+mod lock;
 mod model;
+mod name;
+mod notification;
+mod rate_limit;
+mod reminder;
 mod sheets;
+mod store;
+mod unverified;
 
 use std::{
+    collections::HashMap,
     env, io,
     io::Write,
     ops::Deref,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use futures_util::stream::StreamExt;
 use tracing::{error, info, warn};
 use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt};
@@ -23,13 +33,24 @@ use twilight_gateway::{stream::ShardEventStream, Event, Intents, Shard};
 use twilight_model::{
     http::attachment::Attachment,
     id::{
-        marker::{ApplicationMarker, ChannelMarker, GuildMarker, RoleMarker, WebhookMarker},
+        marker::{
+            ApplicationMarker, ChannelMarker, GuildMarker, RoleMarker, UserMarker, WebhookMarker,
+        },
         Id,
     },
 };
 use twilight_util::link::webhook;
 
-use crate::sheets::Sheets;
+use crate::{
+    database::Database,
+    lock::{SubmissionLocks, UserLocks},
+    model::{form::VerificationForm, verification::FormAnswer},
+    name::DEFAULT_LOWERCASE_PARTICLES,
+    notification::{DEFAULT_APPROVAL_DM_TEMPLATE, DEFAULT_REJECTION_DM_TEMPLATE},
+    rate_limit::RateLimiter,
+    store::{Storage, StoreKind},
+    unverified::UnverifiedAction,
+};
 
 #[derive(Clone)]
 struct WebhookWriter {
@@ -105,10 +126,28 @@ impl<'a> MakeWriter<'a> for WebhookWriter {
 }
 
 struct Config {
+    approval_dm_template: String,
+    database_path: String,
     guild_id: Id<GuildMarker>,
+    name_lowercase_particles: Vec<String>,
+    pending_escalation_hours: u64,
+    pending_escalation_role_id: Option<Id<RoleMarker>>,
+    pending_reminder_hours: u64,
+    pending_reminder_role_id: Option<Id<RoleMarker>>,
+    rejection_dm_template: String,
+    reviewer_role_ids: Vec<Id<RoleMarker>>,
     sheet_id: String,
+    submission_burst_limit: usize,
+    submission_burst_window_secs: i64,
+    submission_cooldown_secs: i64,
+    submission_stores: Vec<StoreKind>,
     token: String,
     tracing_webhook_url: String,
+    unverified_action: UnverifiedAction,
+    unverified_action_days: i64,
+    unverified_dry_run: bool,
+    unverified_reminder_days: i64,
+    verification_form_path: String,
     verification_submissions_channel_id: Id<ChannelMarker>,
     verified_role_id: Id<RoleMarker>,
 }
@@ -116,15 +155,79 @@ struct Config {
 impl Config {
     fn new() -> Result<Self> {
         dotenvy::dotenv()?;
-        Ok(Self {
+        let config = Self {
+            approval_dm_template: env::var("APPROVAL_DM_TEMPLATE")
+                .unwrap_or_else(|_| DEFAULT_APPROVAL_DM_TEMPLATE.to_owned()),
+            database_path: env::var("DATABASE_PATH").unwrap_or_else(|_| "unog_bot.db".to_owned()),
             guild_id: env::var("GUILD_ID")?.parse()?,
+            name_lowercase_particles: env::var("NAME_LOWERCASE_PARTICLES")
+                .unwrap_or_else(|_| DEFAULT_LOWERCASE_PARTICLES.to_owned())
+                .split(',')
+                .map(str::trim)
+                .filter(|particle| !particle.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            pending_escalation_hours: env::var("PENDING_ESCALATION_HOURS")
+                .map_or(Ok(72), |hours| hours.parse())?,
+            pending_escalation_role_id: env::var("PENDING_ESCALATION_ROLE_ID")
+                .ok()
+                .map(|role_id| role_id.parse())
+                .transpose()?,
+            pending_reminder_hours: env::var("PENDING_REMINDER_HOURS")
+                .map_or(Ok(24), |hours| hours.parse())?,
+            pending_reminder_role_id: env::var("PENDING_REMINDER_ROLE_ID")
+                .ok()
+                .map(|role_id| role_id.parse())
+                .transpose()?,
+            rejection_dm_template: env::var("REJECTION_DM_TEMPLATE")
+                .unwrap_or_else(|_| DEFAULT_REJECTION_DM_TEMPLATE.to_owned()),
+            reviewer_role_ids: env::var("REVIEWER_ROLE_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|role_id| !role_id.trim().is_empty())
+                .map(|role_id| Ok(role_id.trim().parse()?))
+                .collect::<Result<_>>()?,
             sheet_id: env::var("SHEET_ID")?,
+            submission_burst_limit: env::var("SUBMISSION_BURST_LIMIT")
+                .map_or(Ok(10), |limit| limit.parse())?,
+            submission_burst_window_secs: env::var("SUBMISSION_BURST_WINDOW_SECS")
+                .map_or(Ok(60), |secs| secs.parse())?,
+            submission_cooldown_secs: env::var("SUBMISSION_COOLDOWN_SECS")
+                .map_or(Ok(10 * 60), |secs| secs.parse())?,
+            submission_stores: env::var("SUBMISSION_STORES")
+                .unwrap_or_else(|_| "sqlite,sheets".to_owned())
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<_>>()?,
             token: env::var("TOKEN")?,
             tracing_webhook_url: env::var("TRACING_WEBHOOK_URL")?,
+            unverified_action: env::var("UNVERIFIED_ACTION")
+                .map_or(Ok(UnverifiedAction::Flag), |action| action.parse())?,
+            unverified_action_days: env::var("UNVERIFIED_ACTION_DAYS")
+                .map_or(Ok(0), |days| days.parse())?,
+            unverified_dry_run: env::var("UNVERIFIED_DRY_RUN")
+                .map_or(Ok(false), |dry_run| dry_run.parse())?,
+            unverified_reminder_days: env::var("UNVERIFIED_REMINDER_DAYS")
+                .map_or(Ok(0), |days| days.parse())?,
+            verification_form_path: env::var("VERIFICATION_FORM_PATH")
+                .unwrap_or_else(|_| "verification_form.json".to_owned()),
             verification_submissions_channel_id: env::var("VERIFICATION_SUBMISSIONS_CHANNEL_ID")?
                 .parse()?,
             verified_role_id: env::var("VERIFIED_ROLE_ID")?.parse()?,
-        })
+        };
+
+        if config.pending_reminder_hours > 0
+            && config.pending_escalation_hours > 0
+            && config.pending_escalation_hours < config.pending_reminder_hours
+        {
+            bail!(
+                "PENDING_ESCALATION_HOURS ({}) can't be less than PENDING_REMINDER_HOURS ({})",
+                config.pending_escalation_hours,
+                config.pending_reminder_hours
+            );
+        }
+
+        Ok(config)
     }
 }
 
@@ -132,7 +235,14 @@ struct ContextInner {
     application_id: Id<ApplicationMarker>,
     client: twilight_http::Client,
     config: Config,
-    sheets: Sheets,
+    form: Arc<VerificationForm>,
+    rate_limiter: RateLimiter,
+    storage: Storage,
+    submission_locks: SubmissionLocks,
+    user_locks: UserLocks,
+    /// The answers of the users whose last submission was invalid, used to
+    /// fill the form again.
+    verification_drafts: Mutex<HashMap<Id<UserMarker>, Vec<FormAnswer>>>,
 }
 
 #[derive(Clone)]
@@ -150,7 +260,15 @@ impl Context {
     async fn new() -> Result<Self> {
         let config = Config::new()?;
         let client = twilight_http::Client::new(config.token.clone());
-        let sheets = Sheets::new(config.sheet_id.clone()).await?;
+        let db = Arc::new(Database::new(&config.database_path)?);
+        let form = Arc::new(VerificationForm::load(&config.verification_form_path)?);
+        let storage = Storage::new(&config.submission_stores, &db, &config.sheet_id, &form)?;
+        let rate_limiter = RateLimiter::new(
+            &db,
+            config.submission_cooldown_secs,
+            config.submission_burst_limit,
+            config.submission_burst_window_secs,
+        )?;
 
         let application_id = client.current_user_application().await?.model().await?.id;
 
@@ -158,14 +276,26 @@ impl Context {
             application_id,
             client,
             config,
-            sheets,
+            form,
+            rate_limiter,
+            storage,
+            submission_locks: SubmissionLocks::default(),
+            user_locks: UserLocks::default(),
+            verification_drafts: Mutex::new(HashMap::new()),
         })))
     }
 
     async fn shards(self) -> Result<Vec<Shard>> {
+        // the intent is privileged so it's only requested when it's needed
+        let intents = if self.tracks_unverified_members() {
+            Intents::GUILD_MEMBERS
+        } else {
+            Intents::empty()
+        };
+
         Ok(twilight_gateway::stream::create_recommended(
             &self.client,
-            twilight_gateway::Config::new(self.config.token.clone(), Intents::empty()),
+            twilight_gateway::Config::new(self.config.token.clone(), intents),
             |_, builder| builder.build(),
         )
         .await?
@@ -179,6 +309,9 @@ impl Context {
                 Ok(())
             }
             Event::InteractionCreate(interaction) => self.handle_interaction(interaction.0).await,
+            Event::MemberAdd(member) => self.handle_member_add(&member),
+            Event::MemberRemove(member) => self.handle_member_remove(&member),
+            Event::MemberUpdate(member) => self.handle_member_update(&member),
             _ => Ok(()),
         };
 
@@ -205,8 +338,36 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::EnvFilter::try_from_default_env()?)
         .try_init()?;
 
+    ctx.storage.load_sheet_columns().await?;
     ctx.set_commands().await?;
 
+    let outbox_ctx = ctx.clone();
+    tokio::spawn(async move {
+        outbox_ctx.storage.retry_outbox_periodically().await;
+    });
+
+    let search_index_ctx = ctx.clone();
+    tokio::spawn(async move {
+        search_index_ctx
+            .storage
+            .refresh_search_index_periodically()
+            .await;
+    });
+
+    if ctx.reminds_pending_submissions() {
+        let reminder_ctx = ctx.clone();
+        tokio::spawn(async move {
+            reminder_ctx.remind_pending_submissions_periodically().await;
+        });
+    }
+
+    if ctx.tracks_unverified_members() {
+        let unverified_ctx = ctx.clone();
+        tokio::spawn(async move {
+            unverified_ctx.check_unverified_members_periodically().await;
+        });
+    }
+
     let mut shards = ctx.clone().shards().await?;
     let mut event_stream = ShardEventStream::new(shards.iter_mut());
 
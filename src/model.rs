@@ -1 +1,20 @@
+pub mod audit;
+pub mod form;
+pub mod member;
+pub mod outbox;
+pub mod reminder;
 pub mod verification;
+
+use std::time::SystemTime;
+
+use anyhow::Result;
+use twilight_model::util::Timestamp;
+
+pub fn now() -> Result<Timestamp> {
+    let unix_secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs()
+        .try_into()?;
+
+    Ok(Timestamp::from_secs(unix_secs)?)
+}
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use twilight_model::{
+    id::{marker::UserMarker, Id},
+    util::Timestamp,
+};
+
+/// A moderation action on a verification submission that's kept in the audit
+/// log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Approve,
+    Reject,
+    Revoke,
+}
+
+impl AuditAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+            Self::Revoke => "revoke",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Approve => "✅ Onayladı",
+            Self::Reject => "❌ Reddetti",
+            Self::Revoke => "🚫 Doğrulamayı kaldırdı",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = Error;
+
+    fn from_str(action: &str) -> Result<Self> {
+        Ok(match action {
+            "approve" => Self::Approve,
+            "reject" => Self::Reject,
+            "revoke" => Self::Revoke,
+            _ => bail!("unknown audit action: {action}"),
+        })
+    }
+}
+
+/// A moderation action on a submission read from the audit log.
+#[derive(Debug, Clone)]
+pub struct AuditLogEntry {
+    pub action: AuditAction,
+    pub created_at: Timestamp,
+    pub reason: Option<String>,
+    /// The moderator who took the action.
+    pub reviewer_id: Id<UserMarker>,
+    pub submission_id: u64,
+}
@@ -0,0 +1,301 @@
+use std::{fs, io::ErrorKind};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike as _, NaiveDate};
+use serde::Deserialize;
+use twilight_model::channel::message::component::{TextInput, TextInputStyle};
+
+use crate::model::now;
+
+/// The custom id of the field whose value the user's nickname is set to.
+pub const NAME_SURNAME_ID: &str = "name-surname";
+
+const DEFAULT_FORM: &str = include_str!("../../verification_form.json");
+const MAX_FIELDS: usize = 5;
+const MAX_YEARS: u8 = 60;
+const MIN_YEAR: i32 = 1900;
+
+/// How the answer to a field is checked before the submission is accepted.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldValidation {
+    /// A past date in the `GG.AA.YYYY` format.
+    Date,
+    Email,
+    /// A whole number of years in the sector.
+    Years,
+}
+
+impl FieldValidation {
+    /// Returns why the value is invalid, if it is.
+    pub fn check(self, value: &str) -> Result<Option<String>> {
+        Ok(match self {
+            Self::Date => {
+                let today = DateTime::from_timestamp(now()?.as_secs(), 0)
+                    .ok_or_else(|| anyhow!("current time is out of range"))?
+                    .date_naive();
+
+                match NaiveDate::parse_from_str(value.trim(), "%d.%m.%Y") {
+                    Ok(date) if date > today => Some("gelecekte bir tarih olamaz".to_owned()),
+                    Ok(date) if date.year() < MIN_YEAR => {
+                        Some("geçerli bir tarih değil".to_owned())
+                    }
+                    Ok(_) => None,
+                    Err(_) => Some("GG.AA.YYYY biçiminde geçerli bir tarih olmalı".to_owned()),
+                }
+            }
+            Self::Email => (!is_valid_email(value.trim()))
+                .then(|| "geçerli bir e-posta adresi değil".to_owned()),
+            Self::Years => match value.trim().parse::<u8>() {
+                Ok(years) if years <= MAX_YEARS => None,
+                _ => Some(format!("0 ile {MAX_YEARS} arasında bir sayı olmalı")),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormFieldStyle {
+    Paragraph,
+    #[default]
+    Short,
+}
+
+/// A question in the verification form.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FormField {
+    pub custom_id: String,
+    pub embed_title: String,
+    /// The value that's saved when the user leaves the field empty.
+    #[serde(default)]
+    pub empty_value: Option<String>,
+    pub label: String,
+    #[serde(default)]
+    pub max_length: Option<u16>,
+    #[serde(default)]
+    pub min_length: Option<u16>,
+    #[serde(default)]
+    pub placeholder: Option<String>,
+    #[serde(default = "FormField::default_required")]
+    pub required: bool,
+    /// Whether the submissions can be searched by the answer to this field.
+    #[serde(default)]
+    pub searchable: bool,
+    /// The header of the sheet column the answer is written to, if any.
+    #[serde(default)]
+    pub sheet_column: Option<String>,
+    #[serde(default)]
+    pub style: FormFieldStyle,
+    #[serde(default)]
+    pub validation: Option<FieldValidation>,
+}
+
+impl FormField {
+    /// Returns the problem with the answer if it's invalid, including the
+    /// field's title.
+    pub fn check(&self, value: &str) -> Result<Option<String>> {
+        if value.trim().is_empty() {
+            return Ok(self
+                .required
+                .then(|| format!("**{}**: boş bırakılamaz", self.embed_title)));
+        }
+
+        Ok(self
+            .validation
+            .map(|validation| validation.check(value))
+            .transpose()?
+            .flatten()
+            .map(|problem| format!("**{}**: {problem}", self.embed_title)))
+    }
+
+    const fn default_required() -> bool {
+        true
+    }
+
+    pub fn text_input(&self, value: Option<String>) -> TextInput {
+        TextInput {
+            custom_id: self.custom_id.clone(),
+            label: self.label.clone(),
+            max_length: self.max_length,
+            min_length: self.min_length,
+            placeholder: self.placeholder.clone(),
+            required: Some(self.required),
+            style: match self.style {
+                FormFieldStyle::Paragraph => TextInputStyle::Paragraph,
+                FormFieldStyle::Short => TextInputStyle::Short,
+            },
+            value,
+        }
+    }
+}
+
+/// The definition of the verification form, which the modal, the submission
+/// embed and the sheet row are generated from.
+#[derive(Clone, Debug, Deserialize)]
+pub struct VerificationForm {
+    pub fields: Vec<FormField>,
+    pub title: String,
+}
+
+impl VerificationForm {
+    /// Returns the field that asks for the user's email, used to find the
+    /// accounts that submitted the same email.
+    pub fn email_field(&self) -> Option<&FormField> {
+        self.fields
+            .iter()
+            .find(|field| field.validation == Some(FieldValidation::Email))
+    }
+
+    pub fn field(&self, custom_id: &str) -> Option<&FormField> {
+        self.fields
+            .iter()
+            .find(|field| field.custom_id == custom_id)
+    }
+
+    /// Reads the form from the given path, falling back to the default form if
+    /// the file doesn't exist.
+    pub fn load(path: &str) -> Result<Self> {
+        let form: Self = match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => serde_json::from_str(DEFAULT_FORM)?,
+            Err(err) => return Err(err.into()),
+        };
+
+        form.validate()?;
+
+        Ok(form)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.fields.is_empty() || self.fields.len() > MAX_FIELDS {
+            bail!("verification form must have between 1 and {MAX_FIELDS} fields");
+        }
+
+        for (idx, field) in self.fields.iter().enumerate() {
+            if self
+                .fields
+                .iter()
+                .take(idx)
+                .any(|other| other.custom_id == field.custom_id)
+            {
+                bail!(
+                    "verification form has duplicate field `{}`",
+                    field.custom_id
+                );
+            }
+        }
+
+        if self.field(NAME_SURNAME_ID).is_none() {
+            bail!("verification form must have a `{NAME_SURNAME_ID}` field");
+        }
+
+        Ok(())
+    }
+}
+
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !local.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldValidation, MAX_YEARS};
+
+    #[test]
+    fn validates_dates() {
+        let cases = [
+            ("01.01.2000", true),
+            (" 01.01.2000 ", true),
+            ("29.02.2024", true),
+            ("29.02.2023", false),
+            ("31.04.2000", false),
+            ("01.01.1900", true),
+            ("31.12.1899", false),
+            ("01.01.3000", false),
+            ("2000-01-01", false),
+            ("1.1.2000", true),
+            ("01.13.2000", false),
+            ("", false),
+        ];
+
+        for (date, valid) in cases {
+            assert_eq!(
+                FieldValidation::Date.check(date).unwrap().is_none(),
+                valid,
+                "{date:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn validates_emails() {
+        let cases = [
+            ("ahmet@example.com", true),
+            (" ahmet@example.com ", true),
+            ("ahmet.yilmaz+unog@mail.example.com.tr", true),
+            ("ahmet@example", false),
+            ("@example.com", false),
+            ("ahmet@", false),
+            ("ahmet", false),
+            ("ahmet yılmaz@example.com", false),
+            ("ahmet@@example.com", false),
+            ("ahmet@exa@mple.com", false),
+            ("ahmet@example..com", false),
+            ("ahmet@-example.com", false),
+            ("ahmet@example.com-", false),
+            ("ahmet@.example.com", false),
+        ];
+
+        for (email, valid) in cases {
+            assert_eq!(
+                FieldValidation::Email.check(email).unwrap().is_none(),
+                valid,
+                "{email:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn validates_years() {
+        let max_years = MAX_YEARS.to_string();
+        let over_max_years = (MAX_YEARS + 1).to_string();
+        let cases = [
+            ("0", true),
+            (" 5 ", true),
+            (max_years.as_str(), true),
+            (over_max_years.as_str(), false),
+            ("300", false),
+            ("-1", false),
+            ("2.5", false),
+            ("beş", false),
+            ("", false),
+        ];
+
+        for (years, valid) in cases {
+            assert_eq!(
+                FieldValidation::Years.check(years).unwrap().is_none(),
+                valid,
+                "{years:?}"
+            );
+        }
+
+        assert_eq!(
+            FieldValidation::Years
+                .check(&over_max_years)
+                .unwrap()
+                .as_deref(),
+            Some(format!("0 ile {MAX_YEARS} arasında bir sayı olmalı").as_str())
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use twilight_model::id::{marker::UserMarker, Id};
+
+/// A member who joined the guild and hasn't submitted the verification form
+/// yet, times are in unix seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct MemberJoin {
+    /// When the member was kicked or flagged for not verifying.
+    pub actioned_at: Option<i64>,
+    pub joined_at: i64,
+    /// When the member was reminded to verify.
+    pub reminded_at: Option<i64>,
+    pub user_id: Id<UserMarker>,
+}
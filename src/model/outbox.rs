@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use twilight_model::util::Timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboxOperation {
+    Append,
+    Update,
+}
+
+impl OutboxOperation {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Append => "append",
+            Self::Update => "update",
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Append => "Ekleme",
+            Self::Update => "Güncelleme",
+        }
+    }
+}
+
+impl FromStr for OutboxOperation {
+    type Err = Error;
+
+    fn from_str(operation: &str) -> Result<Self> {
+        Ok(match operation {
+            "append" => Self::Append,
+            "update" => Self::Update,
+            _ => bail!("unknown outbox operation: {operation}"),
+        })
+    }
+}
+
+/// A write to a mirror submission store that failed and will be retried.
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub attempts: u32,
+    pub created_at: Timestamp,
+    pub id: u64,
+    pub last_error: String,
+    pub next_attempt_at: Timestamp,
+    pub operation: OutboxOperation,
+    pub store: String,
+    pub submission_id: u64,
+}
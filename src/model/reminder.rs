@@ -0,0 +1,17 @@
+/// A ping about submissions that have been pending for too long, each
+/// submission causes each kind of ping only once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    /// Sent after the second threshold to the escalation role.
+    Escalation,
+    Reminder,
+}
+
+impl ReminderKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Escalation => "escalation",
+            Self::Reminder => "reminder",
+        }
+    }
+}
@@ -1,11 +1,160 @@
-use twilight_model::id::{marker::UserMarker, Id};
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use twilight_model::{
+    id::{
+        marker::{MessageMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::model::{form::NAME_SURNAME_ID, now};
+
+/// An answer to a field of the verification form.
+#[derive(Debug, Clone)]
+pub struct FormAnswer {
+    pub custom_id: String,
+    pub value: String,
+}
 
 #[derive(Debug, Clone)]
 pub struct VerificationSubmission {
-    pub birthday: String,
-    pub email: String,
-    pub experience: String,
-    pub name_surname: String,
-    pub organization: String,
+    pub answers: Vec<FormAnswer>,
     pub user_id: Id<UserMarker>,
 }
+
+impl VerificationSubmission {
+    pub fn answer(&self, custom_id: &str) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|answer| answer.custom_id == custom_id)
+            .map(|answer| answer.value.as_str())
+    }
+
+    pub fn name_surname(&self) -> &str {
+        self.answer(NAME_SURNAME_ID).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStatus {
+    Approved,
+    Pending,
+    Rejected,
+    /// Approved at first but then had its verification removed.
+    Revoked,
+}
+
+impl VerificationStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::Pending => "pending",
+            Self::Rejected => "rejected",
+            Self::Revoked => "revoked",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "Doğrulandı" => Some(Self::Approved),
+            "Doğrulanmadı" => Some(Self::Pending),
+            "Reddedildi" => Some(Self::Rejected),
+            "Doğrulaması Kaldırıldı" => Some(Self::Revoked),
+            _ => None,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Approved => "Doğrulandı",
+            Self::Pending => "Doğrulanmadı",
+            Self::Rejected => "Reddedildi",
+            Self::Revoked => "Doğrulaması Kaldırıldı",
+        }
+    }
+}
+
+impl FromStr for VerificationStatus {
+    type Err = Error;
+
+    fn from_str(status: &str) -> Result<Self> {
+        Ok(match status {
+            "approved" => Self::Approved,
+            "pending" => Self::Pending,
+            "rejected" => Self::Rejected,
+            "revoked" => Self::Revoked,
+            _ => bail!("unknown verification status: {status}"),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VerificationSubmissionRecord {
+    pub created_at: Timestamp,
+    pub id: u64,
+    pub message_id: Option<Id<MessageMarker>>,
+    pub reviewed_at: Option<Timestamp>,
+    /// The moderator who approved or rejected the submission.
+    pub reviewer_id: Option<Id<UserMarker>>,
+    pub revoked_at: Option<Timestamp>,
+    /// The moderator who removed the verification after it was approved.
+    pub revoker_id: Option<Id<UserMarker>>,
+    pub status: VerificationStatus,
+    pub status_reason: Option<String>,
+    pub submission: VerificationSubmission,
+    pub updated_at: Timestamp,
+}
+
+impl VerificationSubmissionRecord {
+    pub fn new(id: u64, submission: VerificationSubmission) -> Result<Self> {
+        let created_at = now()?;
+
+        Ok(Self {
+            created_at,
+            id,
+            message_id: None,
+            reviewed_at: None,
+            reviewer_id: None,
+            revoked_at: None,
+            revoker_id: None,
+            status: VerificationStatus::Pending,
+            status_reason: None,
+            submission,
+            updated_at: created_at,
+        })
+    }
+
+    /// Sets the status as decided by the given moderator.
+    pub fn review(
+        &mut self,
+        status: VerificationStatus,
+        reason: Option<String>,
+        reviewer_id: Id<UserMarker>,
+    ) -> Result<()> {
+        self.set_status(status, reason)?;
+        self.reviewed_at = Some(self.updated_at);
+        self.reviewer_id = Some(reviewer_id);
+
+        Ok(())
+    }
+
+    /// Marks the approved submission as revoked by the given moderator,
+    /// keeping who approved it.
+    pub fn revoke(&mut self, reason: String, revoker_id: Id<UserMarker>) -> Result<()> {
+        self.set_status(VerificationStatus::Revoked, Some(reason))?;
+        self.revoked_at = Some(self.updated_at);
+        self.revoker_id = Some(revoker_id);
+
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, status: VerificationStatus, reason: Option<String>) -> Result<()> {
+        self.status = status;
+        self.status_reason = reason;
+        self.updated_at = now()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+/// The maximum length of a nickname Discord accepts, in characters.
+pub const MAX_NICKNAME_LENGTH: usize = 32;
+
+/// The words that are kept lowercase unless they start the name.
+pub const DEFAULT_LOWERCASE_PARTICLES: &str =
+    "al,bin,da,de,del,della,der,di,du,el,ibn,la,le,van,von";
+
+const APOSTROPHES: [char; 3] = ['\'', '’', 'ʼ'];
+const HYPHENS: [char; 2] = ['-', '‐'];
+
+/// A name with its whitespace and casing fixed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedName {
+    pub name: String,
+    /// Whether characters that can't be in a name, such as control or
+    /// invisible characters, were removed.
+    pub removed_chars: bool,
+}
+
+/// Trims the name, collapses the whitespace in it, removes the control and
+/// invisible characters and title cases its words by Turkish rules, keeping
+/// the given particles lowercase.
+pub fn normalize(raw_name: &str, lowercase_particles: &[String]) -> NormalizedName {
+    let mut removed_chars = false;
+    let cleaned = raw_name
+        .chars()
+        .filter(|char| {
+            let keep = char.is_whitespace() || !(char.is_control() || is_invisible(*char));
+            removed_chars |= !keep;
+            keep
+        })
+        .collect::<String>();
+
+    let name = cleaned
+        .split_whitespace()
+        .enumerate()
+        .map(|(idx, word)| {
+            let lowercase_word = to_lowercase(word);
+            if idx > 0
+                && lowercase_particles
+                    .iter()
+                    .any(|particle| to_lowercase(particle) == lowercase_word)
+            {
+                lowercase_word
+            } else {
+                title_case_word(&lowercase_word)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    NormalizedName {
+        name,
+        removed_chars,
+    }
+}
+
+/// Fits the name to [`MAX_NICKNAME_LENGTH`].
+///
+/// The middle names are shortened to their initials first, then dropped, then
+/// the first name is shortened to its initial, so that the surname is kept
+/// whole if possible. The name is cut at the end as a last resort.
+pub fn nickname(full_name: &str) -> String {
+    let name = full_name.trim();
+    if fits(name) {
+        return name.to_owned();
+    }
+
+    let words = name.split_whitespace().collect::<Vec<_>>();
+    let (Some((first, rest)), Some(last)) = (words.split_first(), words.last()) else {
+        return truncate(name);
+    };
+    if rest.is_empty() {
+        return truncate(name);
+    }
+
+    let mut middle = words
+        .get(1..words.len().saturating_sub(1))
+        .unwrap_or_default()
+        .iter()
+        .map(|word| (*word).to_owned())
+        .collect::<Vec<_>>();
+
+    for idx in (0..middle.len()).rev() {
+        if let Some(word) = middle.get_mut(idx) {
+            *word = initial(word);
+        }
+
+        let candidate = join(first, &middle, last);
+        if fits(&candidate) {
+            return candidate;
+        }
+    }
+
+    [join(first, &[], last), join(&initial(first), &[], last)]
+        .into_iter()
+        .find(|candidate| fits(candidate))
+        .unwrap_or_else(|| truncate(name))
+}
+
+fn fits(name: &str) -> bool {
+    name.chars().count() <= MAX_NICKNAME_LENGTH
+}
+
+fn join(first: &str, middle: &[String], last: &str) -> String {
+    let mut words = vec![first];
+    words.extend(middle.iter().map(String::as_str));
+    words.push(last);
+    words.join(" ")
+}
+
+fn initial(word: &str) -> String {
+    word.chars()
+        .next()
+        .map(|char| format!("{char}."))
+        .unwrap_or_default()
+}
+
+fn truncate(name: &str) -> String {
+    name.chars()
+        .take(MAX_NICKNAME_LENGTH)
+        .collect::<String>()
+        .trim_end()
+        .to_owned()
+}
+
+const fn is_invisible(char: char) -> bool {
+    matches!(
+        char,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}' | '\u{FEFF}'
+    )
+}
+
+/// Uppercases the first letter of the lowercase word and of every part of it
+/// after a hyphen, or after an apostrophe that follows a single letter as in
+/// `O'Neil`, since the ones after longer parts are suffixes as in `Ali'nin`.
+fn title_case_word(lowercase_word: &str) -> String {
+    let mut title = String::new();
+    let mut capitalize_next = true;
+    let mut part_len: usize = 0;
+
+    for char in lowercase_word.chars() {
+        if HYPHENS.contains(&char) || APOSTROPHES.contains(&char) {
+            capitalize_next = HYPHENS.contains(&char) || part_len == 1;
+            part_len = 0;
+            title.push(char);
+            continue;
+        }
+
+        if capitalize_next {
+            title.push_str(&to_uppercase(char));
+            capitalize_next = false;
+        } else {
+            title.push(char);
+        }
+        part_len = part_len.saturating_add(1);
+    }
+
+    title
+}
+
+/// Lowercases by Turkish rules, where `I` is the uppercase of `ı` and `İ` is
+/// the uppercase of `i`.
+pub fn to_lowercase(string: &str) -> String {
+    string
+        .chars()
+        .map(|char| match char {
+            'I' => 'ı'.to_string(),
+            'İ' => 'i'.to_string(),
+            other => other.to_lowercase().to_string(),
+        })
+        .collect()
+}
+
+/// Uppercases by Turkish rules, see [`to_lowercase`].
+fn to_uppercase(char: char) -> String {
+    match char {
+        'i' => 'İ'.to_string(),
+        'ı' => 'I'.to_string(),
+        other => other.to_uppercase().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{nickname, normalize, NormalizedName, DEFAULT_LOWERCASE_PARTICLES};
+
+    fn particles() -> Vec<String> {
+        DEFAULT_LOWERCASE_PARTICLES
+            .split(',')
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn normalizes_names() {
+        let cases = [
+            ("ahmet yılmaz", "Ahmet Yılmaz"),
+            ("  AHMET   YILMAZ ", "Ahmet Yılmaz"),
+            ("ahmet\tyılmaz\n", "Ahmet Yılmaz"),
+            ("ismail ışık", "İsmail Işık"),
+            ("İSMAİL IŞIK", "İsmail Işık"),
+            ("IŞIL ILGAZ", "Işıl Ilgaz"),
+            ("iİıI", "İiıı"),
+            ("çağrı öztürk", "Çağrı Öztürk"),
+            ("ÇAĞRI ÖZTÜRK", "Çağrı Öztürk"),
+            ("şule güneş", "Şule Güneş"),
+            ("ayşe-nur kaya", "Ayşe-Nur Kaya"),
+            ("AYŞE-NUR KAYA-ÖZ", "Ayşe-Nur Kaya-Öz"),
+            ("ali-ihsan", "Ali-İhsan"),
+            ("o'neil", "O'Neil"),
+            ("john o’neil", "John O’Neil"),
+            ("d'angelo", "D'Angelo"),
+            ("ali'nin", "Ali'nin"),
+            ("ludwig van beethoven", "Ludwig van Beethoven"),
+            ("HANS VAN DER BERG", "Hans van der Berg"),
+            ("LUDWIG", "Ludwıg"),
+            ("van dijk", "Van Dijk"),
+            ("mehmet bin ali", "Mehmet bin Ali"),
+            ("leonardo da vinci", "Leonardo da Vinci"),
+            ("x", "X"),
+            ("", ""),
+        ];
+
+        for (raw_name, expected) in cases {
+            assert_eq!(
+                normalize(raw_name, &particles()),
+                NormalizedName {
+                    name: expected.to_owned(),
+                    removed_chars: false,
+                },
+                "{raw_name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn uses_given_particles() {
+        let particles = ["ve".to_owned()];
+
+        assert_eq!(normalize("ali ve veli", &particles).name, "Ali ve Veli");
+        assert_eq!(
+            normalize("ludwig van beethoven", &[]).name,
+            "Ludwig Van Beethoven"
+        );
+    }
+
+    #[test]
+    fn removes_invisible_chars() {
+        for raw_name in [
+            "ahmet\u{200B} yılmaz",
+            "\u{FEFF}ahmet yılmaz",
+            "ahmet yıl\u{00AD}maz",
+        ] {
+            let normalized = normalize(raw_name, &particles());
+
+            assert!(normalized.removed_chars, "{raw_name:?}");
+            assert_eq!(normalized.name, "Ahmet Yılmaz", "{raw_name:?}");
+        }
+    }
+
+    #[test]
+    fn fits_nicknames() {
+        let cases = [
+            ("", ""),
+            ("   ", ""),
+            ("Ahmet Yılmaz", "Ahmet Yılmaz"),
+            ("Ahmet Yılmaz ", "Ahmet Yılmaz"),
+            // the length is counted in characters rather than bytes
+            (
+                "Çağrı Şükrü Gökçe Öztürkoğulları",
+                "Çağrı Şükrü Gökçe Öztürkoğulları",
+            ),
+            (
+                "Çağrı Şükrü Gökçe Öztürkoğullarıı",
+                "Çağrı Şükrü G. Öztürkoğullarıı",
+            ),
+            (
+                "Ahmet Mehmet Mustafa Kemal Yılmaz",
+                "Ahmet Mehmet Mustafa K. Yılmaz",
+            ),
+            (
+                "Abdurrahman Mehmet Mustafa Kemal Yılmazoğlu",
+                "Abdurrahman M. M. K. Yılmazoğlu",
+            ),
+            (
+                "Abdurrahman Muhammed Mustafa Kemal Yılmazoğulları",
+                "Abdurrahman Yılmazoğulları",
+            ),
+            (
+                "Abdurrahmanmuhammed Karamustafapaşaoğulları",
+                "A. Karamustafapaşaoğulları",
+            ),
+            (
+                "Abdurrahmanmuhammedmustafakemalyılmaz",
+                "Abdurrahmanmuhammedmustafakemaly",
+            ),
+            (
+                "A Abdurrahmanmuhammedmustafakemalyılmaz",
+                "A Abdurrahmanmuhammedmustafakema",
+            ),
+        ];
+
+        for (name, expected) in cases {
+            let nickname = nickname(name);
+
+            assert_eq!(nickname, expected, "{name:?}");
+            assert!(nickname.chars().count() <= 32, "{name:?}");
+        }
+    }
+}
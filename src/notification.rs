@@ -0,0 +1,59 @@
+use anyhow::Result;
+use twilight_model::{
+    channel::message::embed::{Embed, EmbedField},
+    id::{marker::UserMarker, Id},
+};
+use twilight_util::builder::embed::EmbedFieldBuilder;
+
+use crate::{model::verification::VerificationSubmissionRecord, Context};
+
+pub const DEFAULT_APPROVAL_DM_TEMPLATE: &str =
+    "Hoş geldiniz {name}! Doğrulanma formunuz onaylandı, artık sunucunun tamamına erişebilirsiniz.";
+pub const DEFAULT_REJECTION_DM_TEMPLATE: &str =
+    "Sebep: {reason}\n\nFormu tekrar doldurarak yeniden doğrulanma isteyebilirsiniz.";
+
+impl Context {
+    pub async fn send_dm(&self, user_id: Id<UserMarker>, embed: Embed) -> Result<()> {
+        let channel_id = self
+            .client
+            .create_private_channel(user_id)
+            .await?
+            .model()
+            .await?
+            .id;
+
+        self.client
+            .create_message(channel_id)
+            .embeds(&[embed])?
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Fills the `{name}`, `{user}` and `{reason}` placeholders of a DM template.
+pub fn render_template(
+    template: &str,
+    record: &VerificationSubmissionRecord,
+    status_reason: Option<&str>,
+) -> String {
+    template
+        .replace("\\n", "\n")
+        .replace("{name}", record.submission.name_surname())
+        .replace("{user}", &format!("<@{}>", record.submission.user_id))
+        .replace("{reason}", status_reason.unwrap_or("-"))
+}
+
+/// The field added to the submission embed that shows whether the user could
+/// be notified.
+pub fn dm_status_field(delivered: bool) -> EmbedField {
+    EmbedFieldBuilder::new(
+        "DM",
+        if delivered {
+            "✅ Kullanıcıya iletildi"
+        } else {
+            "⚠️ Kullanıcıya iletilemedi, DM'leri kapalı olabilir"
+        },
+    )
+    .build()
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, Result};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{database::Database, model::now};
+
+/// Why a submission isn't allowed yet, with the unix time it will be allowed
+/// at.
+#[derive(Clone, Copy, Debug)]
+pub enum RateLimited {
+    /// Too many forms are submitted by everyone in the burst window.
+    Global(i64),
+    /// The user submitted a form in the cooldown.
+    User(i64),
+}
+
+impl RateLimited {
+    pub fn message(self) -> String {
+        match self {
+            Self::Global(retry_at) => format!(
+                "Şu anda çok fazla form gönderiliyor, lütfen <t:{retry_at}:R> tekrar deneyin."
+            ),
+            Self::User(retry_at) => format!(
+                "Kısa süre önce bir form gönderdiniz, <t:{retry_at}:R> tekrar deneyebilirsiniz."
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct RateLimitState {
+    /// The time of every user's last submission in the cooldown.
+    last_submissions: HashMap<Id<UserMarker>, i64>,
+    /// The times of all submissions in the burst window, oldest first.
+    recent_submissions: VecDeque<i64>,
+}
+
+/// Limits how often a user can submit the verification form and how many
+/// forms can be submitted in total in a short time.
+///
+/// The submissions are kept in memory and saved to the database, so that the
+/// limits aren't reset when the bot restarts.
+pub struct RateLimiter {
+    burst_limit: usize,
+    burst_window_secs: i64,
+    cooldown_secs: i64,
+    db: Arc<Database>,
+    state: Mutex<RateLimitState>,
+}
+
+impl RateLimiter {
+    /// Returns whether the user has to wait before submitting the form.
+    pub fn check(&self, user_id: Id<UserMarker>) -> Result<Option<RateLimited>> {
+        let now = now()?.as_secs();
+        let mut state = self.state()?;
+        self.forget_old(&mut state, now);
+
+        Ok(self.limited(&state, user_id, now))
+    }
+
+    /// Returns whether the user has to wait before submitting the form,
+    /// counting the submission towards the limits if they don't.
+    ///
+    /// This is done at once so that concurrent submissions can't all pass the
+    /// check before any of them is counted.
+    pub fn check_and_record(&self, user_id: Id<UserMarker>) -> Result<Option<RateLimited>> {
+        let now = now()?.as_secs();
+        let mut state = self.state()?;
+        self.forget_old(&mut state, now);
+
+        if let Some(rate_limited) = self.limited(&state, user_id, now) {
+            return Ok(Some(rate_limited));
+        }
+
+        state.last_submissions.insert(user_id, now);
+        state.recent_submissions.push_back(now);
+        drop(state);
+        self.db
+            .add_submission_attempt(user_id, now, self.forget_before(now))?;
+
+        Ok(None)
+    }
+
+    /// Returns the time before which the submissions don't affect the limits.
+    fn forget_before(&self, now: i64) -> i64 {
+        now.saturating_sub(self.cooldown_secs.max(self.burst_window_secs))
+    }
+
+    fn forget_old(&self, state: &mut RateLimitState, now: i64) {
+        let cooldown_start = now.saturating_sub(self.cooldown_secs);
+        state
+            .last_submissions
+            .retain(|_, attempted_at| *attempted_at > cooldown_start);
+
+        let burst_window_start = now.saturating_sub(self.burst_window_secs);
+        while state
+            .recent_submissions
+            .front()
+            .is_some_and(|attempted_at| *attempted_at <= burst_window_start)
+        {
+            state.recent_submissions.pop_front();
+        }
+    }
+
+    fn limited(
+        &self,
+        state: &RateLimitState,
+        user_id: Id<UserMarker>,
+        now: i64,
+    ) -> Option<RateLimited> {
+        if let Some(last_submission) = state.last_submissions.get(&user_id) {
+            let retry_at = last_submission.saturating_add(self.cooldown_secs);
+            if retry_at > now {
+                return Some(RateLimited::User(retry_at));
+            }
+        }
+
+        if self.burst_limit > 0 && state.recent_submissions.len() >= self.burst_limit {
+            if let Some(oldest) = state.recent_submissions.front() {
+                return Some(RateLimited::Global(
+                    oldest.saturating_add(self.burst_window_secs),
+                ));
+            }
+        }
+
+        None
+    }
+
+    pub fn new(
+        db: &Arc<Database>,
+        cooldown_secs: i64,
+        burst_limit: usize,
+        burst_window_secs: i64,
+    ) -> Result<Self> {
+        let now = now()?.as_secs();
+        let limiter = Self {
+            burst_limit,
+            burst_window_secs,
+            cooldown_secs,
+            db: Arc::clone(db),
+            state: Mutex::new(RateLimitState::default()),
+        };
+
+        {
+            let mut state = limiter.state()?;
+            for (user_id, attempted_at) in db.submission_attempts(limiter.forget_before(now))? {
+                state.last_submissions.insert(user_id, attempted_at);
+                state.recent_submissions.push_back(attempted_at);
+            }
+        }
+
+        Ok(limiter)
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, RateLimitState>> {
+        self.state
+            .lock()
+            .map_err(|err| anyhow!("rate limit mutex is poisoned: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use twilight_model::id::Id;
+
+    use super::{RateLimited, RateLimiter};
+    use crate::database::Database;
+
+    #[test]
+    fn records_only_allowed_submissions() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let limiter = RateLimiter::new(&db, 60, 2, 60).unwrap();
+
+        assert!(limiter.check_and_record(Id::new(1)).unwrap().is_none());
+        assert!(matches!(
+            limiter.check_and_record(Id::new(1)).unwrap(),
+            Some(RateLimited::User(_))
+        ));
+        assert!(limiter.check_and_record(Id::new(2)).unwrap().is_none());
+        assert!(matches!(
+            limiter.check_and_record(Id::new(3)).unwrap(),
+            Some(RateLimited::Global(_))
+        ));
+        assert!(matches!(
+            limiter.check(Id::new(3)).unwrap(),
+            Some(RateLimited::Global(_))
+        ));
+
+        let restarted_limiter = RateLimiter::new(&db, 60, 2, 60).unwrap();
+        assert!(matches!(
+            restarted_limiter.check(Id::new(1)).unwrap(),
+            Some(RateLimited::User(_))
+        ));
+    }
+}
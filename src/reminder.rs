@@ -0,0 +1,247 @@
+use std::{fmt::Write as _, time::Duration};
+
+use anyhow::Result;
+use tracing::{info, warn};
+use twilight_model::{
+    channel::message::Embed,
+    id::{marker::RoleMarker, Id},
+};
+use twilight_util::builder::embed::EmbedBuilder;
+
+use crate::{
+    color::Color,
+    interaction::show_pending_submissions::pending_submission_line,
+    model::{
+        now,
+        reminder::ReminderKind,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+    Context,
+};
+
+const REMINDER_CHECK_INTERVAL: Duration = Duration::from_mins(10);
+const MAX_LISTED_SUBMISSIONS: usize = 15;
+const SECS_PER_HOUR: u64 = 60 * 60;
+
+impl Context {
+    fn escalation_role_id(&self) -> Option<Id<RoleMarker>> {
+        self.config
+            .pending_escalation_role_id
+            .or(self.config.pending_reminder_role_id)
+    }
+
+    /// Sends a digest of the submissions pending longer than
+    /// `PENDING_REMINDER_HOURS` to the submissions channel if there's a
+    /// submission that hasn't been reminded yet, pinging the escalation role
+    /// if one is also pending longer than `PENDING_ESCALATION_HOURS`.
+    async fn remind_pending_submissions(&self) -> Result<()> {
+        let now = now()?.as_secs();
+        let due_reminders = |created_at: i64| self.submission_due_reminders(created_at, now);
+        let is_overdue =
+            |created_at: i64| due_reminders(created_at).contains(&ReminderKind::Escalation);
+
+        let mut stale_submissions = self
+            .storage
+            .submissions()
+            .await?
+            .into_iter()
+            .filter(|record| {
+                record.status == VerificationStatus::Pending
+                    && !due_reminders(record.created_at.as_secs()).is_empty()
+            })
+            .collect::<Vec<_>>();
+        stale_submissions.sort_by_key(|record| (record.created_at.as_secs(), record.id));
+
+        let reminded = self.storage.reminded_submissions(ReminderKind::Reminder)?;
+        let escalated = self
+            .storage
+            .reminded_submissions(ReminderKind::Escalation)?;
+        let unreminded_ids = stale_submissions
+            .iter()
+            .filter(|record| {
+                due_reminders(record.created_at.as_secs()).contains(&ReminderKind::Reminder)
+                    && !reminded.contains(&record.id)
+            })
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        let unescalated_ids = stale_submissions
+            .iter()
+            .filter(|record| {
+                is_overdue(record.created_at.as_secs()) && !escalated.contains(&record.id)
+            })
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        if unreminded_ids.is_empty() && unescalated_ids.is_empty() {
+            return Ok(());
+        }
+
+        let embeds = [self.stale_submissions_embed(&stale_submissions, now)?];
+
+        let mut lines = vec![];
+        if !unreminded_ids.is_empty() {
+            if let Some(role_id) = self.config.pending_reminder_role_id {
+                lines.push(format!("<@&{role_id}>"));
+            }
+        }
+        if !unescalated_ids.is_empty() {
+            if let Some(role_id) = self.escalation_role_id() {
+                lines.push(format!(
+                    "🚨 <@&{role_id}> {} form {} saatten uzun süredir bekliyor",
+                    unescalated_ids.len(),
+                    self.config.pending_escalation_hours
+                ));
+            }
+        }
+        let content = lines.join("\n");
+
+        let mut message = self
+            .client
+            .create_message(self.config.verification_submissions_channel_id)
+            .embeds(&embeds)?;
+        if !content.is_empty() {
+            message = message.content(&content)?;
+        }
+        message.await?;
+
+        info!(
+            ?unreminded_ids,
+            ?unescalated_ids,
+            "reminded pending verification submissions"
+        );
+
+        self.storage
+            .add_reminded_submissions(ReminderKind::Reminder, &unreminded_ids, now)?;
+        self.storage
+            .add_reminded_submissions(ReminderKind::Escalation, &unescalated_ids, now)?;
+
+        Ok(())
+    }
+
+    /// Checks for the submissions pending longer than the thresholds
+    /// regularly.
+    pub async fn remind_pending_submissions_periodically(&self) -> ! {
+        let mut interval = tokio::time::interval(REMINDER_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.remind_pending_submissions().await {
+                warn!(?err, "couldn't remind pending submissions");
+            }
+        }
+    }
+
+    /// Returns whether the reminders or the escalation are enabled.
+    pub fn reminds_pending_submissions(&self) -> bool {
+        self.config.pending_reminder_hours > 0 || self.config.pending_escalation_hours > 0
+    }
+
+    /// Lists the submissions pending longer than the thresholds, marking the
+    /// ones that are escalated.
+    fn stale_submissions_embed(
+        &self,
+        stale_submissions: &[VerificationSubmissionRecord],
+        now: i64,
+    ) -> Result<Embed> {
+        let mut description = String::new();
+        for record in stale_submissions.iter().take(MAX_LISTED_SUBMISSIONS) {
+            let marker = if self
+                .submission_due_reminders(record.created_at.as_secs(), now)
+                .contains(&ReminderKind::Escalation)
+            {
+                "🚨 "
+            } else {
+                ""
+            };
+            writeln!(
+                description,
+                "{marker}{}",
+                pending_submission_line(self, record)?
+            )?;
+        }
+        let remaining = stale_submissions
+            .len()
+            .saturating_sub(MAX_LISTED_SUBMISSIONS);
+        if remaining > 0 {
+            writeln!(description, "\nve {remaining} form daha")?;
+        }
+
+        Ok(EmbedBuilder::new()
+            .title(format!(
+                "⏰ {} doğrulanma formu {} saatten uzun süredir bekliyor",
+                stale_submissions.len(),
+                // the escalation threshold is the only one if the reminders
+                // are disabled
+                if self.config.pending_reminder_hours > 0 {
+                    self.config.pending_reminder_hours
+                } else {
+                    self.config.pending_escalation_hours
+                }
+            ))
+            .description(description)
+            .color(Color::Pending.into())
+            .build())
+    }
+
+    /// Returns the reminders due for a submission created at the given time.
+    fn submission_due_reminders(&self, created_at: i64, now: i64) -> Vec<ReminderKind> {
+        due_reminders(
+            // the submissions created in the future aren't pending yet
+            u64::try_from(now.saturating_sub(created_at)).unwrap_or_default(),
+            self.config.pending_reminder_hours,
+            self.config.pending_escalation_hours,
+        )
+    }
+}
+
+/// Returns the reminders that are due for a submission that's been pending for
+/// the given seconds, a threshold of 0 hours disabling its reminder.
+fn due_reminders(
+    pending_secs: u64,
+    reminder_hours: u64,
+    escalation_hours: u64,
+) -> Vec<ReminderKind> {
+    let is_due = |hours: u64| hours > 0 && pending_secs >= hours.saturating_mul(SECS_PER_HOUR);
+
+    [
+        (ReminderKind::Reminder, reminder_hours),
+        (ReminderKind::Escalation, escalation_hours),
+    ]
+    .into_iter()
+    .filter(|&(_, hours)| is_due(hours))
+    .map(|(kind, _)| kind)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{due_reminders, SECS_PER_HOUR};
+    use crate::model::reminder::ReminderKind::{Escalation, Reminder};
+
+    #[test]
+    fn selects_due_reminders() {
+        for (pending_hours, reminder_hours, escalation_hours, expected) in [
+            (0, 24, 72, vec![]),
+            (23, 24, 72, vec![]),
+            (24, 24, 72, vec![Reminder]),
+            (71, 24, 72, vec![Reminder]),
+            (72, 24, 72, vec![Reminder, Escalation]),
+            (71, 0, 72, vec![]),
+            (72, 0, 72, vec![Escalation]),
+            (100, 24, 0, vec![Reminder]),
+            (1000, 0, 0, vec![]),
+            (24, 24, 24, vec![Reminder, Escalation]),
+        ] {
+            assert_eq!(
+                due_reminders(
+                    pending_hours * SECS_PER_HOUR,
+                    reminder_hours,
+                    escalation_hours
+                ),
+                expected,
+                "pending for {pending_hours} hours with thresholds {reminder_hours} and \
+                 {escalation_hours}"
+            );
+        }
+    }
+}
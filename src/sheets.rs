@@ -1,24 +1,208 @@
-use std::num::ParseIntError;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+};
 
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime};
 use google_sheets4::{
-    api::{SpreadsheetMethods, ValueRange},
+    api::{BatchUpdateValuesRequest, SpreadsheetMethods, ValueRange},
     hyper::{client::HttpConnector, Client},
     hyper_rustls::{HttpsConnector, HttpsConnectorBuilder},
     oauth2::{read_service_account_key, ServiceAccountAuthenticator},
     Sheets as GoogleSheets,
 };
-use twilight_model::id::{marker::UserMarker, Id};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use twilight_model::{id::Id, util::Timestamp};
+
+use crate::{
+    model::{
+        form::VerificationForm,
+        verification::{
+            FormAnswer, VerificationStatus, VerificationSubmission, VerificationSubmissionRecord,
+        },
+    },
+    store::SubmissionStore,
+};
+
+const DISCORD_EPOCH_MILLIS: u64 = 1_420_070_400_000;
+
+/// A logical field of a verification submission that's kept in a column of
+/// the sheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SheetField {
+    CreatedAt,
+    ReviewedAt,
+    ReviewerId,
+    RevokedAt,
+    RevokerId,
+    Status,
+    StatusReason,
+    SubmissionId,
+    UpdatedAt,
+    UserId,
+}
+
+impl SheetField {
+    const ALL: [Self; 10] = [
+        Self::CreatedAt,
+        Self::ReviewedAt,
+        Self::ReviewerId,
+        Self::RevokedAt,
+        Self::RevokerId,
+        Self::Status,
+        Self::StatusReason,
+        Self::SubmissionId,
+        Self::UpdatedAt,
+        Self::UserId,
+    ];
+
+    const fn header(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "Gönderilme Zamanı",
+            Self::ReviewedAt => "İnceleme Zamanı",
+            Self::ReviewerId => "İnceleyen ID",
+            Self::RevokedAt => "Kaldırılma Zamanı",
+            Self::RevokerId => "Kaldıran ID",
+            Self::Status => "Durum",
+            Self::StatusReason => "Sebep",
+            Self::SubmissionId => "Form ID",
+            Self::UpdatedAt => "Güncellenme Zamanı",
+            Self::UserId => "Discord ID",
+        }
+    }
+
+    const fn is_required(self) -> bool {
+        !matches!(
+            self,
+            Self::CreatedAt
+                | Self::ReviewedAt
+                | Self::ReviewerId
+                | Self::RevokedAt
+                | Self::RevokerId
+                | Self::StatusReason
+                | Self::UpdatedAt
+        )
+    }
+}
+
+/// The headers of required fields that are missing in the sheet.
+#[derive(Debug)]
+pub struct MissingHeadersError(Vec<String>);
+
+impl Display for MissingHeadersError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the sheet is missing the required headers: {}",
+            self.0.join(", ")
+        )
+    }
+}
+
+impl Error for MissingHeadersError {}
+
+/// The indices of the columns of the fields and the form answers in the
+/// sheet, read from its header row.
+#[derive(Debug)]
+struct SheetColumns {
+    answer_indices: HashMap<String, usize>,
+    indices: HashMap<SheetField, usize>,
+    width: usize,
+}
+
+impl SheetColumns {
+    fn answer_idx(&self, custom_id: &str) -> Option<usize> {
+        self.answer_indices.get(custom_id).copied()
+    }
+
+    fn cell(&self, row: &[Value], field: SheetField) -> String {
+        cell(row, self.idx(field))
+    }
+
+    fn idx(&self, field: SheetField) -> Option<usize> {
+        self.indices.get(&field).copied()
+    }
+
+    fn last_column_name(&self) -> String {
+        column_name(self.width.saturating_sub(1))
+    }
+
+    fn new(header: &[Value], form: &VerificationForm) -> Result<Self, MissingHeadersError> {
+        let header_idx = |name: &str| {
+            header
+                .iter()
+                .position(|value| value.as_str().map(str::trim) == Some(name))
+        };
 
-use crate::model::verification::VerificationSubmission;
+        let mut indices = HashMap::new();
+        let mut answer_indices = HashMap::new();
+        let mut missing_headers = vec![];
 
+        for field in SheetField::ALL {
+            match header_idx(field.header()) {
+                Some(idx) => {
+                    indices.insert(field, idx);
+                }
+                None if field.is_required() => missing_headers.push(field.header().to_owned()),
+                None => {}
+            }
+        }
+
+        for field in &form.fields {
+            let Some(sheet_column) = field.sheet_column.as_ref() else {
+                continue;
+            };
+
+            match header_idx(sheet_column) {
+                Some(idx) => {
+                    answer_indices.insert(field.custom_id.clone(), idx);
+                }
+                None => missing_headers.push(sheet_column.clone()),
+            }
+        }
+
+        if !missing_headers.is_empty() {
+            return Err(MissingHeadersError(missing_headers));
+        }
+
+        let width = indices
+            .values()
+            .chain(answer_indices.values())
+            .max()
+            .map_or(0, |max_idx| max_idx.saturating_add(1));
+
+        Ok(Self {
+            answer_indices,
+            indices,
+            width,
+        })
+    }
+
+    fn range(&self, field: SheetField, row_idx: usize) -> Option<String> {
+        self.idx(field)
+            .map(|idx| format!("{}{row_idx}", column_name(idx)))
+    }
+}
+
+/// Connects to Google Sheets lazily so that the bot can start even if the
+/// sheet is unreachable.
 pub struct Sheets {
+    columns: OnceCell<SheetColumns>,
+    form: Arc<VerificationForm>,
+    hub: OnceCell<GoogleSheets<HttpsConnector<HttpConnector>>>,
     sheet_id: String,
-    sheets: GoogleSheets<HttpsConnector<HttpConnector>>,
 }
 
 impl Sheets {
-    pub async fn new(sheet_id: String) -> Result<Self> {
+    async fn columns(&self) -> Result<&SheetColumns> {
+        self.columns.get_or_try_init(|| self.read_columns()).await
+    }
+
+    async fn connect() -> Result<GoogleSheets<HttpsConnector<HttpConnector>>> {
         let hyper_client = Client::builder().build(
             HttpsConnectorBuilder::new()
                 .with_native_roots()
@@ -32,30 +216,152 @@ impl Sheets {
             .build()
             .await?;
 
-        let sheets = GoogleSheets::new(hyper_client, auth);
+        Ok(GoogleSheets::new(hyper_client, auth))
+    }
+
+    /// Reads the header row of the sheet to map the fields to columns.
+    ///
+    /// The returned error can be downcast to [`MissingHeadersError`] if the
+    /// sheet is reachable but doesn't have the required headers.
+    pub async fn load_columns(&self) -> Result<()> {
+        self.columns().await?;
+        Ok(())
+    }
+
+    pub const fn new(sheet_id: String, form: Arc<VerificationForm>) -> Self {
+        Self {
+            columns: OnceCell::const_new(),
+            form,
+            hub: OnceCell::const_new(),
+            sheet_id,
+        }
+    }
+
+    async fn read_columns(&self) -> Result<SheetColumns> {
+        let (_, header) = self
+            .req()
+            .await?
+            .values_get(&self.sheet_id, "1:1")
+            .doit()
+            .await?;
+
+        Ok(SheetColumns::new(
+            header
+                .values
+                .unwrap_or_default()
+                .first()
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            &self.form,
+        )?)
+    }
+
+    async fn req(&self) -> Result<SpreadsheetMethods<'_, HttpsConnector<HttpConnector>>> {
+        Ok(self
+            .hub
+            .get_or_try_init(Self::connect)
+            .await?
+            .spreadsheets())
+    }
+
+    async fn row_idx(&self, id: u64) -> Result<usize> {
+        let columns = self.columns().await?;
+
+        self.rows()
+            .await?
+            .iter()
+            .position(|row| columns.cell(row, SheetField::SubmissionId) == id.to_string())
+            .ok_or_else(|| anyhow!("verification submission {id} not found in sheet"))?
+            .checked_add(2)
+            .ok_or_else(|| anyhow!("submission row idx doesnt fit in usize"))
+    }
+
+    async fn rows(&self) -> Result<Vec<Vec<Value>>> {
+        let columns = self.columns().await?;
+        let (_, rows) = self
+            .req()
+            .await?
+            .values_get(
+                &self.sheet_id,
+                &format!("A2:{}", columns.last_column_name()),
+            )
+            .doit()
+            .await?;
 
-        Ok(Self { sheet_id, sheets })
+        Ok(rows.values.unwrap_or_default())
     }
+}
+
+impl SubmissionStore for Sheets {
+    const NAME: &'static str = "sheets";
+
+    async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        let columns = self.columns().await?;
+        let submission = &record.submission;
+
+        let mut row = vec![Value::String(String::new()); columns.width];
+        let mut set_cell = |idx: Option<usize>, value: String| {
+            if let Some(cell) = idx.and_then(|column| row.get_mut(column)) {
+                *cell = value.into();
+            }
+        };
+
+        for (field, value) in [
+            (SheetField::CreatedAt, format_timestamp(record.created_at)?),
+            (
+                SheetField::ReviewedAt,
+                record
+                    .reviewed_at
+                    .map(format_timestamp)
+                    .transpose()?
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::ReviewerId,
+                record
+                    .reviewer_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::RevokedAt,
+                record
+                    .revoked_at
+                    .map(format_timestamp)
+                    .transpose()?
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::RevokerId,
+                record
+                    .revoker_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            (SheetField::Status, record.status.label().to_owned()),
+            (
+                SheetField::StatusReason,
+                record.status_reason.clone().unwrap_or_default(),
+            ),
+            (SheetField::SubmissionId, record.id.to_string()),
+            (SheetField::UpdatedAt, format_timestamp(record.updated_at)?),
+            (SheetField::UserId, submission.user_id.to_string()),
+        ] {
+            set_cell(columns.idx(field), value);
+        }
+
+        for answer in &submission.answers {
+            set_cell(columns.answer_idx(&answer.custom_id), answer.value.clone());
+        }
 
-    pub async fn append_verification_submission(
-        &self,
-        submission: VerificationSubmission,
-    ) -> Result<()> {
         let value = ValueRange {
             major_dimension: None,
             range: None,
-            values: Some(vec![vec![
-                submission.user_id.to_string().into(),
-                submission.name_surname.into(),
-                submission.email.into(),
-                submission.birthday.into(),
-                submission.experience.into(),
-                submission.organization.into(),
-                "Doğrulanmadı".into(),
-            ]]),
+            values: Some(vec![row]),
         };
 
         self.req()
+            .await?
             .values_append(value, &self.sheet_id, "A:A")
             .value_input_option("USER_ENTERED")
             .doit()
@@ -64,46 +370,263 @@ impl Sheets {
         Ok(())
     }
 
-    pub async fn set_verification_submission_approved(
-        &self,
-        user_id: Id<UserMarker>,
-    ) -> Result<()> {
-        let (_, user_id_column) = self.req().values_get(&self.sheet_id, "A:A").doit().await?;
-        let user_id_row_idx = user_id_column
-            .values
-            .ok_or_else(|| anyhow!("user ids column has no value"))?
-            .into_iter()
-            .skip(1)
-            .map(|values| {
-                values
-                    .first()
-                    .and_then(|value| value.as_str())
-                    .ok_or_else(|| anyhow!("value in user id column isnt string"))
-                    .and_then(|value| value.parse().map_err(|err: ParseIntError| err.into()))
-            })
-            .collect::<Result<Vec<Id<UserMarker>>>>()?
+    async fn submission(&self, id: u64) -> Result<Option<VerificationSubmissionRecord>> {
+        Ok(self
+            .submissions()
+            .await?
             .into_iter()
-            .position(|id| id == user_id)
-            .ok_or_else(|| anyhow!("user id to approve not found in sheet"))?
-            .checked_add(2)
-            .ok_or_else(|| anyhow!("user id row idx doesnt fit in usize"))?;
+            .find(|record| record.id == id))
+    }
 
-        let value = ValueRange {
-            major_dimension: None,
-            range: None,
-            values: Some(vec![vec!["Doğrulandı".into()]]),
-        };
+    async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        let columns = self.columns().await?;
+
+        self.rows()
+            .await?
+            .iter()
+            .filter(|row| !columns.cell(row, SheetField::SubmissionId).is_empty())
+            .map(|row| record_from_row(&self.form, columns, row))
+            .collect()
+    }
+
+    async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        let columns = self.columns().await?;
+        let row_idx = self.row_idx(record.id).await?;
+
+        let mut data = vec![];
+        for (field, value) in [
+            (
+                SheetField::ReviewedAt,
+                record
+                    .reviewed_at
+                    .map(format_timestamp)
+                    .transpose()?
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::ReviewerId,
+                record
+                    .reviewer_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::RevokedAt,
+                record
+                    .revoked_at
+                    .map(format_timestamp)
+                    .transpose()?
+                    .unwrap_or_default(),
+            ),
+            (
+                SheetField::RevokerId,
+                record
+                    .revoker_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            (SheetField::Status, record.status.label().to_owned()),
+            (
+                SheetField::StatusReason,
+                record.status_reason.clone().unwrap_or_default(),
+            ),
+            (SheetField::UpdatedAt, format_timestamp(record.updated_at)?),
+        ] {
+            if let Some(range) = columns.range(field, row_idx) {
+                data.push(ValueRange {
+                    major_dimension: None,
+                    range: Some(range),
+                    values: Some(vec![vec![value.into()]]),
+                });
+            }
+        }
 
         self.req()
-            .values_update(value, &self.sheet_id, &format!("G{user_id_row_idx}"))
-            .value_input_option("USER_ENTERED")
+            .await?
+            .values_batch_update(
+                BatchUpdateValuesRequest {
+                    data: Some(data),
+                    value_input_option: Some("USER_ENTERED".to_owned()),
+                    ..BatchUpdateValuesRequest::default()
+                },
+                &self.sheet_id,
+            )
             .doit()
             .await?;
 
         Ok(())
     }
+}
+
+fn cell(row: &[Value], idx: Option<usize>) -> String {
+    idx.and_then(|column| row.get(column))
+        .map(|value| {
+            value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_owned)
+        })
+        .unwrap_or_default()
+}
+
+fn column_name(idx: usize) -> String {
+    let mut name = String::new();
+    let mut remaining = idx.saturating_add(1);
+
+    while remaining > 0 {
+        remaining = remaining.saturating_sub(1);
+        let offset = u8::try_from(remaining.rem_euclid(26)).unwrap_or_default();
+        name.insert(0, char::from(b'A'.saturating_add(offset)));
+        remaining /= 26;
+    }
 
-    fn req(&self) -> SpreadsheetMethods<'_, HttpsConnector<HttpConnector>> {
-        self.sheets.spreadsheets()
+    name
+}
+
+fn format_timestamp(timestamp: Timestamp) -> Result<String> {
+    Ok(DateTime::from_timestamp(timestamp.as_secs(), 0)
+        .ok_or_else(|| anyhow!("timestamp is out of range"))?
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string())
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<Timestamp> {
+    Ok(Timestamp::from_secs(
+        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")?
+            .and_utc()
+            .timestamp(),
+    )?)
+}
+
+fn record_from_row(
+    form: &VerificationForm,
+    columns: &SheetColumns,
+    row: &[Value],
+) -> Result<VerificationSubmissionRecord> {
+    let id: u64 = columns.cell(row, SheetField::SubmissionId).parse()?;
+    let status_reason = columns.cell(row, SheetField::StatusReason);
+    let reviewed_at = columns.cell(row, SheetField::ReviewedAt);
+    let reviewer_id = columns.cell(row, SheetField::ReviewerId);
+    let revoked_at = columns.cell(row, SheetField::RevokedAt);
+    let revoker_id = columns.cell(row, SheetField::RevokerId);
+
+    // the sheet doesn't keep exact timestamps, so they're derived from the id,
+    // which is the snowflake of the interaction the submission was made in
+    let created_at_millis = (id >> 22)
+        .checked_add(DISCORD_EPOCH_MILLIS)
+        .ok_or_else(|| anyhow!("submission id timestamp overflows"))?;
+    let created_at = Timestamp::from_micros(
+        created_at_millis
+            .checked_mul(1000)
+            .ok_or_else(|| anyhow!("submission id timestamp overflows"))?
+            .try_into()?,
+    )?;
+
+    Ok(VerificationSubmissionRecord {
+        created_at,
+        id,
+        message_id: None,
+        reviewed_at: (!reviewed_at.is_empty())
+            .then(|| parse_timestamp(&reviewed_at))
+            .transpose()?,
+        reviewer_id: (!reviewer_id.is_empty())
+            .then(|| {
+                Id::new_checked(reviewer_id.parse()?)
+                    .ok_or_else(|| anyhow!("reviewer id in sheet is zero"))
+            })
+            .transpose()?,
+        revoked_at: (!revoked_at.is_empty())
+            .then(|| parse_timestamp(&revoked_at))
+            .transpose()?,
+        revoker_id: (!revoker_id.is_empty())
+            .then(|| {
+                Id::new_checked(revoker_id.parse()?)
+                    .ok_or_else(|| anyhow!("revoker id in sheet is zero"))
+            })
+            .transpose()?,
+        status: VerificationStatus::from_label(&columns.cell(row, SheetField::Status))
+            .ok_or_else(|| anyhow!("unknown verification status in sheet"))?,
+        status_reason: (!status_reason.is_empty()).then_some(status_reason),
+        submission: VerificationSubmission {
+            answers: form
+                .fields
+                .iter()
+                .map(|field| FormAnswer {
+                    custom_id: field.custom_id.clone(),
+                    value: cell(row, columns.answer_idx(&field.custom_id)),
+                })
+                .collect(),
+            user_id: Id::new_checked(columns.cell(row, SheetField::UserId).parse()?)
+                .ok_or_else(|| anyhow!("user id in sheet is zero"))?,
+        },
+        updated_at: created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{column_name, SheetColumns, SheetField};
+    use crate::model::form::VerificationForm;
+
+    const ANSWER_HEADERS: [&str; 5] = [
+        "İsim Soyisim",
+        "E-Posta Adresi",
+        "Doğum Tarihi",
+        "Tecrübe",
+        "Kurum veya Ekip",
+    ];
+
+    fn form() -> VerificationForm {
+        serde_json::from_str(include_str!("../verification_form.json")).unwrap()
+    }
+
+    fn header(names: &[&str]) -> Vec<Value> {
+        names.iter().map(|&name| Value::from(name)).collect()
+    }
+
+    #[test]
+    fn requires_headers() {
+        let err = SheetColumns::new(&header(&ANSWER_HEADERS), &form()).unwrap_err();
+
+        assert_eq!(err.0, ["Durum", "Form ID", "Discord ID"]);
+    }
+
+    #[test]
+    fn maps_columns_in_any_order() {
+        let mut names = vec!["Durum", "Sebep", "Discord ID"];
+        names.extend(ANSWER_HEADERS.iter().rev());
+        names.push(" Form ID ");
+
+        let columns = SheetColumns::new(&header(&names), &form()).unwrap();
+
+        assert_eq!(columns.idx(SheetField::Status), Some(0));
+        assert_eq!(columns.idx(SheetField::StatusReason), Some(1));
+        assert_eq!(columns.idx(SheetField::UserId), Some(2));
+        assert_eq!(columns.idx(SheetField::SubmissionId), Some(8));
+        assert_eq!(columns.idx(SheetField::ReviewerId), None);
+        assert_eq!(columns.answer_idx("organization"), Some(3));
+        assert_eq!(columns.answer_idx("name-surname"), Some(7));
+        assert_eq!(columns.last_column_name(), "I");
+    }
+
+    #[test]
+    fn names_columns_past_z() {
+        let mut names = vec![""; 26];
+        names.extend(["Form ID", "Discord ID", "Durum"]);
+        names.extend(ANSWER_HEADERS);
+
+        let columns = SheetColumns::new(&header(&names), &form()).unwrap();
+
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+        assert_eq!(
+            columns.range(SheetField::SubmissionId, 2).as_deref(),
+            Some("AA2")
+        );
+        assert_eq!(columns.last_column_name(), "AH");
     }
 }
@@ -0,0 +1,744 @@
+mod memory;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    str::FromStr,
+    sync::{self, Arc},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Error, Result};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use twilight_model::{
+    id::{
+        marker::{MessageMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::{
+    database::Database,
+    model::{
+        audit::{AuditAction, AuditLogEntry},
+        form::VerificationForm,
+        member::MemberJoin,
+        now,
+        outbox::{OutboxEntry, OutboxOperation},
+        reminder::ReminderKind,
+        verification::{VerificationStatus, VerificationSubmissionRecord},
+    },
+    sheets::{MissingHeadersError, Sheets},
+    store::memory::MemoryStore,
+};
+
+const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+const OUTBOX_BASE_BACKOFF_SECS: i64 = 30;
+const OUTBOX_MAX_BACKOFF_SECS: i64 = 60 * 60;
+/// How often the search index is reloaded, to pick up the changes made to the
+/// primary store outside the bot.
+const SEARCH_INDEX_REFRESH_INTERVAL: Duration = Duration::from_mins(15);
+/// The reason of the submissions rejected because their message couldn't be
+/// posted.
+const UNPOSTED_SUBMISSION_REASON: &str = "Form yetkililere iletilemedi.";
+
+pub trait SubmissionStore {
+    const NAME: &'static str;
+
+    async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()>;
+
+    async fn submission(&self, id: u64) -> Result<Option<VerificationSubmissionRecord>>;
+
+    async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>>;
+
+    async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreKind {
+    Memory,
+    Sheets,
+    Sqlite,
+}
+
+impl FromStr for StoreKind {
+    type Err = Error;
+
+    fn from_str(kind: &str) -> Result<Self> {
+        Ok(match kind.trim() {
+            MemoryStore::NAME => Self::Memory,
+            Sheets::NAME => Self::Sheets,
+            Database::NAME => Self::Sqlite,
+            _ => bail!("unknown submission store: {kind}"),
+        })
+    }
+}
+
+pub enum Store {
+    Memory(MemoryStore),
+    Sheets(Box<Sheets>),
+    Sqlite(Arc<Database>),
+}
+
+impl Store {
+    async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        match self {
+            Self::Memory(store) => store.append(record).await,
+            Self::Sheets(store) => store.append(record).await,
+            Self::Sqlite(store) => store.append(record).await,
+        }
+    }
+
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Memory(_) => MemoryStore::NAME,
+            Self::Sheets(_) => Sheets::NAME,
+            Self::Sqlite(_) => Database::NAME,
+        }
+    }
+
+    async fn submission(&self, id: u64) -> Result<Option<VerificationSubmissionRecord>> {
+        match self {
+            Self::Memory(store) => store.submission(id).await,
+            Self::Sheets(store) => store.submission(id).await,
+            Self::Sqlite(store) => store.submission(id).await,
+        }
+    }
+
+    async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        match self {
+            Self::Memory(store) => store.submissions().await,
+            Self::Sheets(store) => store.submissions().await,
+            Self::Sqlite(store) => store.submissions().await,
+        }
+    }
+
+    async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        match self {
+            Self::Memory(store) => store.update(record).await,
+            Self::Sheets(store) => store.update(record).await,
+            Self::Sqlite(store) => store.update(record).await,
+        }
+    }
+}
+
+/// The configured submission stores.
+///
+/// Lookups and listings are served by the primary store, writes go to the
+/// primary store first and are then mirrored to the rest. A mirror failing
+/// doesn't fail the write, instead the write is queued in the outbox and
+/// retried with exponential backoff.
+///
+/// The submissions are also kept in a search index in memory, since reading
+/// them from the primary store can be too slow to answer autocompletes.
+pub struct Storage {
+    db: Arc<Database>,
+    mirrors: Vec<Store>,
+    outbox_lock: Mutex<()>,
+    primary: Store,
+    /// The submissions by their ids, `None` until they're loaded from the
+    /// primary store.
+    search_index: sync::Mutex<Option<BTreeMap<u64, VerificationSubmissionRecord>>>,
+}
+
+impl Storage {
+    pub fn add_audit_log_entry(
+        &self,
+        record: &VerificationSubmissionRecord,
+        action: AuditAction,
+    ) -> Result<()> {
+        self.db.add_audit_log_entry(record, action)
+    }
+
+    pub fn add_member_join(&self, user_id: Id<UserMarker>, joined_at: i64) -> Result<()> {
+        self.db.add_member_join(user_id, joined_at)
+    }
+
+    pub fn add_missing_member_joins(&self, member_joins: &[(Id<UserMarker>, i64)]) -> Result<()> {
+        self.db.add_missing_member_joins(member_joins)
+    }
+
+    pub fn add_reminded_submissions(
+        &self,
+        kind: ReminderKind,
+        submission_ids: &[u64],
+        sent_at: i64,
+    ) -> Result<()> {
+        self.db
+            .add_reminded_submissions(kind, submission_ids, sent_at)
+    }
+
+    pub async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        self.primary.append(record).await?;
+        self.index(record)?;
+
+        for mirror in &self.mirrors {
+            if let Err(err) = mirror.append(record).await {
+                warn!(
+                    ?err,
+                    store = mirror.name(),
+                    "couldn't append verification submission, queueing it for retry: {record:#?}"
+                );
+                self.enqueue(mirror, OutboxOperation::Append, record.id, &err)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the submission and then posts its message with `post`, returning
+    /// the message's id.
+    ///
+    /// The submission is saved first so that the message's buttons never
+    /// point to a submission that isn't saved, and it's rejected if the
+    /// message can't be posted so that it doesn't block the user from
+    /// submitting again.
+    pub async fn append_and_post<F: Future<Output = Result<Id<MessageMarker>>>>(
+        &self,
+        record: &VerificationSubmissionRecord,
+        post: F,
+    ) -> Result<Id<MessageMarker>> {
+        self.append(record).await?;
+
+        let err = match post.await {
+            Ok(message_id) => return Ok(message_id),
+            Err(err) => err,
+        };
+
+        let mut rejected_record = record.clone();
+        rejected_record.set_status(
+            VerificationStatus::Rejected,
+            Some(UNPOSTED_SUBMISSION_REASON.to_owned()),
+        )?;
+        if let Err(update_err) = self.update(&rejected_record).await {
+            warn!(
+                ?update_err,
+                "couldn't reject verification submission whose message couldn't be posted: \
+                 {record:#?}"
+            );
+        }
+
+        Err(err)
+    }
+
+    pub fn audit_log_entries(&self, user_id: Id<UserMarker>) -> Result<Vec<AuditLogEntry>> {
+        self.db.audit_log_entries(user_id)
+    }
+
+    pub fn delete_member_join(&self, user_id: Id<UserMarker>) -> Result<()> {
+        self.db.delete_member_join(user_id)
+    }
+
+    fn enqueue(
+        &self,
+        mirror: &Store,
+        operation: OutboxOperation,
+        submission_id: u64,
+        err: &Error,
+    ) -> Result<()> {
+        self.db.enqueue_outbox_entry(
+            mirror.name(),
+            operation,
+            submission_id,
+            &format!("{err:#}"),
+            next_attempt_at(0)?,
+        )
+    }
+
+    pub async fn has_submission(&self, id: u64) -> Result<bool> {
+        Ok(self.primary.submission(id).await?.is_some())
+    }
+
+    /// Saves a submission only to the primary store, for the submissions the
+    /// mirrors already have.
+    pub async fn import(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        self.primary.append(record).await?;
+        self.index(record)
+    }
+
+    /// Puts the written submission in the search index if it's loaded.
+    fn index(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        if let Some(index) = self
+            .search_index
+            .lock()
+            .map_err(|err| anyhow!("search index mutex is poisoned: {err}"))?
+            .as_mut()
+        {
+            index.insert(record.id, record.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the submissions in the search index, oldest first, or `None`
+    /// if it isn't loaded yet.
+    pub fn indexed_submissions(&self) -> Result<Option<Vec<VerificationSubmissionRecord>>> {
+        Ok(self
+            .search_index
+            .lock()
+            .map_err(|err| anyhow!("search index mutex is poisoned: {err}"))?
+            .as_ref()
+            .map(|index| index.values().cloned().collect()))
+    }
+
+    /// Maps the fields to the columns of the sheet stores, failing only if a
+    /// sheet is reachable but doesn't have the required headers.
+    pub async fn load_sheet_columns(&self) -> Result<()> {
+        for store in self.mirrors.iter().chain([&self.primary]) {
+            let Store::Sheets(sheets) = store else {
+                continue;
+            };
+
+            if let Err(err) = sheets.load_columns().await {
+                if err.is::<MissingHeadersError>() {
+                    return Err(err);
+                }
+                warn!(
+                    ?err,
+                    "couldn't load sheet columns, will try again when writing"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn member_joins(&self) -> Result<Vec<MemberJoin>> {
+        self.db.member_joins()
+    }
+
+    pub fn new(
+        kinds: &[StoreKind],
+        db: &Arc<Database>,
+        sheet_id: &str,
+        form: &Arc<VerificationForm>,
+    ) -> Result<Self> {
+        let mut stores = kinds.iter().map(|kind| match *kind {
+            StoreKind::Memory => Store::Memory(MemoryStore::default()),
+            StoreKind::Sheets => {
+                Store::Sheets(Box::new(Sheets::new(sheet_id.to_owned(), Arc::clone(form))))
+            }
+            StoreKind::Sqlite => Store::Sqlite(Arc::clone(db)),
+        });
+
+        let primary = stores
+            .next()
+            .ok_or_else(|| anyhow!("no submission store is configured"))?;
+
+        Ok(Self {
+            db: Arc::clone(db),
+            mirrors: stores.collect(),
+            outbox_lock: Mutex::new(()),
+            primary,
+            search_index: sync::Mutex::new(None),
+        })
+    }
+
+    pub fn outbox(&self) -> Result<Vec<OutboxEntry>> {
+        self.db.outbox_entries()
+    }
+
+    /// Reloads the search index from the primary store, returning the
+    /// submissions in it, oldest first.
+    pub async fn refresh_search_index(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        let index = self
+            .primary
+            .submissions()
+            .await?
+            .into_iter()
+            .map(|record| (record.id, record))
+            .collect::<BTreeMap<_, _>>();
+        let submissions = index.values().cloned().collect();
+
+        *self
+            .search_index
+            .lock()
+            .map_err(|err| anyhow!("search index mutex is poisoned: {err}"))? = Some(index);
+
+        Ok(submissions)
+    }
+
+    pub async fn refresh_search_index_periodically(&self) -> ! {
+        let mut interval = tokio::time::interval(SEARCH_INDEX_REFRESH_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.refresh_search_index().await {
+                warn!(?err, "couldn't refresh search index");
+            }
+        }
+    }
+
+    pub fn reminded_submissions(&self, kind: ReminderKind) -> Result<HashSet<u64>> {
+        self.db.reminded_submissions(kind)
+    }
+
+    /// Retries the writes in the outbox, only the ones whose backoff has
+    /// passed unless `force` is set.
+    pub async fn retry_outbox(&self, force: bool) -> Result<()> {
+        // held until the retries are done so that the periodic retry and a
+        // flush don't write the same entry twice
+        let _lock = self.outbox_lock.lock().await;
+        let now = now()?;
+        let mut blocked = HashSet::new();
+
+        for entry in self.db.outbox_entries()? {
+            let key = (entry.store.clone(), entry.submission_id);
+            if blocked.contains(&key) {
+                continue;
+            }
+            if !force && entry.next_attempt_at.as_secs() > now.as_secs() {
+                blocked.insert(key);
+                continue;
+            }
+
+            let Some(mirror) = self
+                .mirrors
+                .iter()
+                .find(|store| store.name() == entry.store)
+            else {
+                warn!(
+                    ?entry,
+                    "outbox entry's store isn't configured anymore, dropping it"
+                );
+                self.db.delete_outbox_entry(entry.id)?;
+                continue;
+            };
+
+            let result = match self.primary.submission(entry.submission_id).await {
+                Ok(Some(record)) => match entry.operation {
+                    OutboxOperation::Append => mirror.append(&record).await,
+                    OutboxOperation::Update => mirror.update(&record).await,
+                },
+                Ok(None) => {
+                    warn!(
+                        ?entry,
+                        "outbox entry's submission doesn't exist anymore, dropping it"
+                    );
+                    self.db.delete_outbox_entry(entry.id)?;
+                    continue;
+                }
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(()) => {
+                    info!(?entry, "retried outbox entry successfully");
+                    self.db.delete_outbox_entry(entry.id)?;
+                }
+                Err(err) => {
+                    warn!(?err, ?entry, "couldn't retry outbox entry");
+                    self.db.set_outbox_entry_failed(
+                        entry.id,
+                        &format!("{err:#}"),
+                        next_attempt_at(entry.attempts)?,
+                    )?;
+                    blocked.insert(key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn retry_outbox_periodically(&self) -> ! {
+        let mut interval = tokio::time::interval(OUTBOX_RETRY_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.retry_outbox(false).await {
+                warn!(?err, "couldn't retry outbox");
+            }
+        }
+    }
+
+    pub fn set_member_actioned(&self, user_id: Id<UserMarker>, actioned_at: i64) -> Result<()> {
+        self.db.set_member_actioned(user_id, actioned_at)
+    }
+
+    pub fn set_member_reminded(&self, user_id: Id<UserMarker>, reminded_at: i64) -> Result<()> {
+        self.db.set_member_reminded(user_id, reminded_at)
+    }
+
+    pub async fn submission(&self, id: u64) -> Result<VerificationSubmissionRecord> {
+        self.primary
+            .submission(id)
+            .await?
+            .ok_or_else(|| anyhow!("verification submission {id} not found"))
+    }
+
+    pub async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        self.primary.submissions().await
+    }
+
+    pub async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        self.primary.update(record).await?;
+        self.index(record)?;
+
+        for mirror in &self.mirrors {
+            // the update has to wait for the queued writes so that it's not
+            // overwritten by them or tries to update a row that isn't there yet
+            if self.db.has_outbox_entry(mirror.name(), record.id)? {
+                self.enqueue(
+                    mirror,
+                    OutboxOperation::Update,
+                    record.id,
+                    &anyhow!("waiting for earlier writes"),
+                )?;
+                continue;
+            }
+
+            if let Err(err) = mirror.update(record).await {
+                warn!(
+                    ?err,
+                    store = mirror.name(),
+                    "couldn't update verification submission, queueing it for retry: {record:#?}"
+                );
+                self.enqueue(mirror, OutboxOperation::Update, record.id, &err)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn next_attempt_at(attempts: u32) -> Result<Timestamp> {
+    let backoff_secs = i64::checked_pow(2, attempts)
+        .and_then(|multiplier| multiplier.checked_mul(OUTBOX_BASE_BACKOFF_SECS))
+        .map_or(OUTBOX_MAX_BACKOFF_SECS, |secs| {
+            secs.min(OUTBOX_MAX_BACKOFF_SECS)
+        });
+
+    Ok(Timestamp::from_secs(
+        now()?.as_secs().saturating_add(backoff_secs),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::anyhow;
+    use twilight_model::id::Id;
+
+    use super::{MemoryStore, Storage, Store, StoreKind, SubmissionStore as _};
+    use crate::{
+        database::Database,
+        model::{
+            form::VerificationForm,
+            now,
+            outbox::OutboxOperation,
+            verification::{
+                FormAnswer, VerificationStatus, VerificationSubmission,
+                VerificationSubmissionRecord,
+            },
+        },
+    };
+
+    fn record(id: u64) -> VerificationSubmissionRecord {
+        VerificationSubmissionRecord::new(
+            id,
+            VerificationSubmission {
+                answers: vec![
+                    FormAnswer {
+                        custom_id: "name-surname".to_owned(),
+                        value: "Ahmet Yılmaz".to_owned(),
+                    },
+                    FormAnswer {
+                        custom_id: "email".to_owned(),
+                        value: "ahmet@example.com".to_owned(),
+                    },
+                ],
+                user_id: Id::new(id),
+            },
+        )
+        .unwrap()
+    }
+
+    /// Returns a storage with the memory store as the primary store and the
+    /// database as its mirror.
+    fn storage(db: &Arc<Database>) -> Storage {
+        let form = Arc::new(
+            serde_json::from_str::<VerificationForm>(include_str!("../verification_form.json"))
+                .unwrap(),
+        );
+
+        Storage::new(&[StoreKind::Memory, StoreKind::Sqlite], db, "", &form).unwrap()
+    }
+
+    async fn check_store(store: &Store) {
+        let mut first = record(1);
+        store.append(&first).await.unwrap();
+        store.append(&record(2)).await.unwrap();
+        store.append(&first).await.unwrap_err();
+        assert!(store.submission(3).await.unwrap().is_none());
+
+        first
+            .review(
+                VerificationStatus::Rejected,
+                Some("eksik bilgi".to_owned()),
+                Id::new(10),
+            )
+            .unwrap();
+        store.update(&first).await.unwrap();
+
+        let stored = store.submission(1).await.unwrap().unwrap();
+        assert_eq!(stored.status, VerificationStatus::Rejected);
+        assert_eq!(stored.status_reason.as_deref(), Some("eksik bilgi"));
+        assert_eq!(stored.reviewer_id, Some(Id::new(10)));
+        assert_eq!(stored.submission.name_surname(), "Ahmet Yılmaz");
+        assert_eq!(stored.submission.answer("email"), Some("ahmet@example.com"));
+
+        let mut second = store.submission(2).await.unwrap().unwrap();
+        second
+            .review(VerificationStatus::Approved, None, Id::new(10))
+            .unwrap();
+        second
+            .revoke("sahte bilgi".to_owned(), Id::new(11))
+            .unwrap();
+        store.update(&second).await.unwrap();
+
+        let revoked = store.submission(2).await.unwrap().unwrap();
+        assert_eq!(revoked.status, VerificationStatus::Revoked);
+        assert_eq!(revoked.reviewer_id, Some(Id::new(10)));
+        assert_eq!(revoked.revoker_id, Some(Id::new(11)));
+        assert!(revoked.revoked_at.is_some());
+
+        let ids = store
+            .submissions()
+            .await
+            .unwrap()
+            .iter()
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn memory_store_stores_submissions() {
+        check_store(&Store::Memory(MemoryStore::default())).await;
+    }
+
+    #[tokio::test]
+    async fn sqlite_store_stores_submissions() {
+        check_store(&Store::Sqlite(Arc::new(Database::new(":memory:").unwrap()))).await;
+    }
+
+    #[tokio::test]
+    async fn storage_mirrors_writes() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let storage = storage(&db);
+
+        let mut record = record(1);
+        storage.append(&record).await.unwrap();
+        record
+            .review(VerificationStatus::Approved, None, Id::new(10))
+            .unwrap();
+        storage.update(&record).await.unwrap();
+
+        assert_eq!(
+            storage.submission(1).await.unwrap().status,
+            VerificationStatus::Approved
+        );
+        assert_eq!(
+            db.submission(1).await.unwrap().unwrap().status,
+            VerificationStatus::Approved
+        );
+        assert!(storage.outbox().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn storage_imports_only_to_primary_store() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let storage = storage(&db);
+
+        storage.import(&record(1)).await.unwrap();
+
+        assert!(storage.has_submission(1).await.unwrap());
+        assert!(db.submission(1).await.unwrap().is_none());
+        assert!(storage.outbox().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn storage_indexes_written_submissions() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let storage = storage(&db);
+
+        storage.append(&record(2)).await.unwrap();
+        assert!(storage.indexed_submissions().unwrap().is_none());
+
+        let ids = storage
+            .refresh_search_index()
+            .await
+            .unwrap()
+            .iter()
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [2]);
+
+        let mut first = record(1);
+        storage.append(&first).await.unwrap();
+        first
+            .review(VerificationStatus::Approved, None, Id::new(10))
+            .unwrap();
+        storage.update(&first).await.unwrap();
+
+        let indexed = storage.indexed_submissions().unwrap().unwrap();
+        assert_eq!(
+            indexed.iter().map(|record| record.id).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(indexed[0].status, VerificationStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn storage_rejects_submissions_whose_message_isnt_posted() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let storage = storage(&db);
+
+        storage
+            .append_and_post(&record(1), async { Err(anyhow!("test")) })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            storage.submission(1).await.unwrap().status,
+            VerificationStatus::Rejected
+        );
+        assert_eq!(
+            db.submission(1).await.unwrap().unwrap().status,
+            VerificationStatus::Rejected
+        );
+
+        let message_id = storage
+            .append_and_post(&record(2), async { Ok(Id::new(20)) })
+            .await
+            .unwrap();
+        assert_eq!(message_id, Id::new(20));
+        assert_eq!(
+            storage.submission(2).await.unwrap().status,
+            VerificationStatus::Pending
+        );
+    }
+
+    #[tokio::test]
+    async fn storage_drops_outbox_entries_of_missing_submissions() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let storage = storage(&db);
+
+        db.enqueue_outbox_entry(
+            Database::NAME,
+            OutboxOperation::Append,
+            1,
+            "test",
+            now().unwrap(),
+        )
+        .unwrap();
+        storage.retry_outbox(true).await.unwrap();
+
+        assert!(storage.outbox().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::{Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{model::verification::VerificationSubmissionRecord, store::SubmissionStore};
+
+#[derive(Debug, Default)]
+pub struct MemoryStore(Mutex<BTreeMap<u64, VerificationSubmissionRecord>>);
+
+impl MemoryStore {
+    fn records(&self) -> Result<MutexGuard<'_, BTreeMap<u64, VerificationSubmissionRecord>>> {
+        self.0
+            .lock()
+            .map_err(|err| anyhow!("memory store mutex is poisoned: {err}"))
+    }
+}
+
+impl SubmissionStore for MemoryStore {
+    const NAME: &'static str = "memory";
+
+    async fn append(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        match self.records()?.entry(record.id) {
+            Entry::Occupied(_) => bail!("verification submission {} already exists", record.id),
+            Entry::Vacant(entry) => {
+                entry.insert(record.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn submission(&self, id: u64) -> Result<Option<VerificationSubmissionRecord>> {
+        Ok(self.records()?.get(&id).cloned())
+    }
+
+    async fn submissions(&self) -> Result<Vec<VerificationSubmissionRecord>> {
+        Ok(self.records()?.values().cloned().collect())
+    }
+
+    async fn update(&self, record: &VerificationSubmissionRecord) -> Result<()> {
+        *self
+            .records()?
+            .get_mut(&record.id)
+            .ok_or_else(|| anyhow!("verification submission {} not found", record.id))? =
+            record.clone();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,455 @@
+use std::{collections::HashSet, fmt::Write as _, str::FromStr, time::Duration};
+
+use anyhow::{bail, Error, Result};
+use tracing::{info, warn};
+use twilight_http::request::AuditLogReason as _;
+use twilight_model::{
+    channel::message::Embed,
+    gateway::payload::incoming::{MemberAdd, MemberRemove, MemberUpdate},
+    id::{marker::UserMarker, Id},
+    util::Timestamp,
+};
+use twilight_util::builder::embed::EmbedBuilder;
+
+use crate::{
+    color::Color,
+    discord_error::{is_api_error, UNKNOWN_MEMBER_ERROR_CODE},
+    model::{member::MemberJoin, now},
+    Context,
+};
+
+const UNVERIFIED_CHECK_INTERVAL: Duration = Duration::from_hours(24);
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+const MAX_LISTED_MEMBERS: usize = 30;
+/// The most members Discord returns in a page of the member list.
+const MEMBERS_PAGE_LIMIT: u16 = 1000;
+
+/// What's done to the members who don't verify in `UNVERIFIED_ACTION_DAYS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnverifiedAction {
+    /// Listing them in the submissions channel for the moderators.
+    Flag,
+    Kick,
+}
+
+impl UnverifiedAction {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Flag => "işaretlendi",
+            Self::Kick => "sunucudan çıkarıldı",
+        }
+    }
+}
+
+impl FromStr for UnverifiedAction {
+    type Err = Error;
+
+    fn from_str(action: &str) -> Result<Self> {
+        Ok(match action.trim() {
+            "flag" => Self::Flag,
+            "kick" => Self::Kick,
+            _ => bail!("unknown unverified member action: {action}"),
+        })
+    }
+}
+
+/// What was done or, in the dry run mode, would be done to a member.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MemberOutcome {
+    Actioned,
+    Reminded,
+}
+
+impl Context {
+    /// Tracks the unverified members who joined before the tracking was
+    /// enabled or while the bot was offline, by when they joined the guild.
+    async fn add_missing_member_joins(&self) -> Result<()> {
+        let submitted_user_ids = self
+            .storage
+            .submissions()
+            .await?
+            .into_iter()
+            .map(|record| record.submission.user_id)
+            .collect::<HashSet<_>>();
+
+        let mut member_joins = vec![];
+        let mut after = None;
+        loop {
+            let mut request = self
+                .client
+                .guild_members(self.config.guild_id)
+                .limit(MEMBERS_PAGE_LIMIT)?;
+            if let Some(last_id) = after {
+                request = request.after(last_id);
+            }
+            let members = request.await?.models().await?;
+
+            after = members.last().map(|member| member.user.id);
+            let is_last_page = members.len() < usize::from(MEMBERS_PAGE_LIMIT);
+
+            member_joins.extend(
+                members
+                    .into_iter()
+                    .filter(|member| {
+                        !member.user.bot
+                            && !member.roles.contains(&self.config.verified_role_id)
+                            && !submitted_user_ids.contains(&member.user.id)
+                    })
+                    .map(|member| (member.user.id, member.joined_at.as_secs())),
+            );
+
+            if is_last_page || after.is_none() {
+                break;
+            }
+        }
+
+        self.storage.add_missing_member_joins(&member_joins)?;
+        info!(
+            members = member_joins.len(),
+            "added unverified members who aren't tracked"
+        );
+
+        Ok(())
+    }
+
+    async fn apply_outcome(
+        &self,
+        outcome: MemberOutcome,
+        user_id: Id<UserMarker>,
+        now: i64,
+    ) -> Result<()> {
+        match outcome {
+            MemberOutcome::Actioned => {
+                if self.config.unverified_action == UnverifiedAction::Kick {
+                    self.client
+                        .remove_guild_member(self.config.guild_id, user_id)
+                        .reason(&format!(
+                            "{} gün içinde doğrulanmadı",
+                            self.config.unverified_action_days
+                        ))?
+                        .await?;
+                }
+                self.storage.set_member_actioned(user_id, now)?;
+            }
+            MemberOutcome::Reminded => {
+                // the member is reminded once even if their dms are closed
+                if let Err(err) = self
+                    .send_dm(user_id, self.unverified_reminder_embed()?)
+                    .await
+                {
+                    warn!(?err, ?user_id, "couldn't remind unverified member");
+                }
+                self.storage.set_member_reminded(user_id, now)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reminds the members who haven't submitted the form in
+    /// `UNVERIFIED_REMINDER_DAYS` and kicks or flags the ones who haven't in
+    /// `UNVERIFIED_ACTION_DAYS`, only reporting them in the dry run mode.
+    async fn check_unverified_members(&self) -> Result<()> {
+        let now = now()?.as_secs();
+        let submitted_user_ids = self
+            .storage
+            .submissions()
+            .await?
+            .into_iter()
+            .map(|record| record.submission.user_id)
+            .collect::<HashSet<_>>();
+
+        let mut outcomes = vec![];
+        for member_join in self.storage.member_joins()? {
+            // the member pressed the button so they're the reviewers' concern
+            // now
+            if submitted_user_ids.contains(&member_join.user_id) {
+                self.storage.delete_member_join(member_join.user_id)?;
+                continue;
+            }
+
+            let Some(outcome) = due_outcome(
+                &member_join,
+                now,
+                self.config.unverified_reminder_days,
+                self.config.unverified_action_days,
+            ) else {
+                continue;
+            };
+
+            if !self.is_unverified_member(member_join.user_id).await? {
+                self.storage.delete_member_join(member_join.user_id)?;
+                continue;
+            }
+
+            if !self.config.unverified_dry_run {
+                if let Err(err) = self.apply_outcome(outcome, member_join.user_id, now).await {
+                    warn!(
+                        ?err,
+                        user_id = ?member_join.user_id,
+                        "couldn't remind or action unverified member"
+                    );
+                    continue;
+                }
+            }
+
+            outcomes.push((member_join, outcome));
+        }
+
+        self.report_unverified_members(&outcomes, now).await
+    }
+
+    pub async fn check_unverified_members_periodically(&self) -> ! {
+        if let Err(err) = self.add_missing_member_joins().await {
+            warn!(?err, "couldn't add unverified members who aren't tracked");
+        }
+
+        let mut interval = tokio::time::interval(UNVERIFIED_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.check_unverified_members().await {
+                warn!(?err, "couldn't check unverified members");
+            }
+        }
+    }
+
+    pub fn handle_member_add(&self, member: &MemberAdd) -> Result<()> {
+        if member.guild_id != self.config.guild_id || member.user.bot {
+            return Ok(());
+        }
+
+        self.storage
+            .add_member_join(member.user.id, now()?.as_secs())
+    }
+
+    pub fn handle_member_remove(&self, member: &MemberRemove) -> Result<()> {
+        if member.guild_id != self.config.guild_id {
+            return Ok(());
+        }
+
+        self.storage.delete_member_join(member.user.id)
+    }
+
+    pub fn handle_member_update(&self, member: &MemberUpdate) -> Result<()> {
+        if member.guild_id != self.config.guild_id
+            || !member.roles.contains(&self.config.verified_role_id)
+        {
+            return Ok(());
+        }
+
+        self.storage.delete_member_join(member.user.id)
+    }
+
+    /// Returns whether the user is still in the guild without the verified
+    /// role, checked before doing anything in case an event was missed.
+    async fn is_unverified_member(&self, user_id: Id<UserMarker>) -> Result<bool> {
+        match self
+            .client
+            .guild_member(self.config.guild_id, user_id)
+            .await
+        {
+            Ok(response) => Ok(!response
+                .model()
+                .await?
+                .roles
+                .contains(&self.config.verified_role_id)),
+            Err(err) if is_api_error(&err, UNKNOWN_MEMBER_ERROR_CODE) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Lists the reminded and actioned members in the submissions channel.
+    async fn report_unverified_members(
+        &self,
+        outcomes: &[(MemberJoin, MemberOutcome)],
+        now: i64,
+    ) -> Result<()> {
+        if outcomes.is_empty() {
+            return Ok(());
+        }
+
+        let mut reminded = vec![];
+        let mut actioned = vec![];
+        for &(member_join, outcome) in outcomes {
+            let line = format!(
+                "- <@{}>, <t:{}:R> katıldı",
+                member_join.user_id, member_join.joined_at
+            );
+            match outcome {
+                MemberOutcome::Actioned => actioned.push(line),
+                MemberOutcome::Reminded => reminded.push(line),
+            }
+        }
+
+        info!(
+            reminded = reminded.len(),
+            actioned = actioned.len(),
+            dry_run = self.config.unverified_dry_run,
+            "checked unverified members"
+        );
+
+        let mut description = String::new();
+        for (title, lines) in [
+            (
+                format!(
+                    "{} gün içinde doğrulanmadığı için {}",
+                    self.config.unverified_action_days,
+                    self.config.unverified_action.label()
+                ),
+                actioned,
+            ),
+            (
+                format!(
+                    "{} gün içinde doğrulanmadığı için DM'den hatırlatıldı",
+                    self.config.unverified_reminder_days
+                ),
+                reminded,
+            ),
+        ] {
+            if lines.is_empty() {
+                continue;
+            }
+
+            writeln!(description, "**{title}**")?;
+            for line in lines.iter().take(MAX_LISTED_MEMBERS) {
+                writeln!(description, "{line}")?;
+            }
+            let remaining = lines.len().saturating_sub(MAX_LISTED_MEMBERS);
+            if remaining > 0 {
+                writeln!(description, "ve {remaining} kullanıcı daha")?;
+            }
+            writeln!(description)?;
+        }
+
+        let embed = if self.config.unverified_dry_run {
+            EmbedBuilder::new()
+                .title("🧪 Deneme modu: Doğrulanmayan kullanıcılar")
+                .description(format!(
+                    "`UNVERIFIED_DRY_RUN` açık olduğu için hiçbir şey yapılmadı, kapatılınca \
+                     şunlar yapılacak:\n\n{description}"
+                ))
+        } else {
+            EmbedBuilder::new()
+                .title("⚠️ Doğrulanmayan kullanıcılar")
+                .description(description)
+        }
+        .timestamp(Timestamp::from_secs(now)?)
+        .color(Color::Pending.into())
+        .build();
+
+        self.client
+            .create_message(self.config.verification_submissions_channel_id)
+            .embeds(&[embed])?
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the members who don't verify are reminded or actioned,
+    /// in which case the guild members are tracked.
+    pub fn tracks_unverified_members(&self) -> bool {
+        self.config.unverified_reminder_days > 0 || self.config.unverified_action_days > 0
+    }
+
+    fn unverified_reminder_embed(&self) -> Result<Embed> {
+        let mut description = format!(
+            "Sunucuya katılalı {} gün oldu ama henüz doğrulanmadınız. Sunucunun tamamına erişmek \
+             için doğrulanma kanalındaki butonla formu doldurabilirsiniz.",
+            self.config.unverified_reminder_days
+        );
+        if self.config.unverified_action == UnverifiedAction::Kick
+            && self.config.unverified_action_days > 0
+        {
+            write!(
+                description,
+                "\n\nKatıldıktan sonraki {} gün içinde doğrulanmazsanız sunucudan \
+                 çıkarılacaksınız.",
+                self.config.unverified_action_days
+            )?;
+        }
+
+        Ok(EmbedBuilder::new()
+            .title("👋 Doğrulanmayı unutmayın")
+            .description(description)
+            .color(Color::Pending.into())
+            .build())
+    }
+}
+
+/// Returns what's due for the member, a threshold of 0 days disabling its
+/// outcome.
+fn due_outcome(
+    member_join: &MemberJoin,
+    now: i64,
+    reminder_days: i64,
+    action_days: i64,
+) -> Option<MemberOutcome> {
+    let joined_secs = now.saturating_sub(member_join.joined_at);
+    let is_due = |days: i64| days > 0 && joined_secs >= days.saturating_mul(SECS_PER_DAY);
+
+    if member_join.actioned_at.is_none() && is_due(action_days) {
+        Some(MemberOutcome::Actioned)
+    } else if member_join.reminded_at.is_none()
+        && member_join.actioned_at.is_none()
+        && is_due(reminder_days)
+    {
+        Some(MemberOutcome::Reminded)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::{due_outcome, MemberOutcome, SECS_PER_DAY};
+    use crate::model::member::MemberJoin;
+
+    #[test]
+    fn selects_due_outcomes() {
+        let now = 100 * SECS_PER_DAY;
+        let joined_days_ago = |days: i64| MemberJoin {
+            actioned_at: None,
+            joined_at: now - days * SECS_PER_DAY,
+            reminded_at: None,
+            user_id: Id::new(1),
+        };
+        let reminded = |member_join: MemberJoin| MemberJoin {
+            reminded_at: Some(now),
+            ..member_join
+        };
+        let actioned = |member_join: MemberJoin| MemberJoin {
+            actioned_at: Some(now),
+            ..member_join
+        };
+
+        for (member_join, reminder_days, action_days, expected) in [
+            (joined_days_ago(0), 3, 7, None),
+            (joined_days_ago(2), 3, 7, None),
+            (joined_days_ago(3), 3, 7, Some(MemberOutcome::Reminded)),
+            (reminded(joined_days_ago(3)), 3, 7, None),
+            (joined_days_ago(7), 3, 7, Some(MemberOutcome::Actioned)),
+            (
+                reminded(joined_days_ago(7)),
+                3,
+                7,
+                Some(MemberOutcome::Actioned),
+            ),
+            (actioned(joined_days_ago(30)), 3, 7, None),
+            (joined_days_ago(30), 0, 7, Some(MemberOutcome::Actioned)),
+            (joined_days_ago(30), 3, 0, Some(MemberOutcome::Reminded)),
+            (joined_days_ago(30), 0, 0, None),
+            // joined in the future because of clock skew
+            (joined_days_ago(-1), 3, 7, None),
+        ] {
+            assert_eq!(
+                due_outcome(&member_join, now, reminder_days, action_days),
+                expected,
+                "{member_join:?} with thresholds {reminder_days} and {action_days}"
+            );
+        }
+    }
+}